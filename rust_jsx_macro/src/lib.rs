@@ -0,0 +1,738 @@
+extern crate proc_macro;
+
+use std::fmt;
+use std::mem;
+
+use proc_macro2::{Delimiter, Ident, Literal, Span, TokenStream, TokenTree};
+use quote::{quote, quote_spanned, ToTokens};
+
+use rust_jsx::render::{attribute_context, Context, Output};
+use rust_jsx::{
+    SnaxAttribute, SnaxElse, SnaxItem, SnaxName, SnaxPath, SnaxSelfClosingTag, SnaxTag, SnaxTagName,
+};
+
+/// Elements that can't have children. A self-closing tag with one of these
+/// names is rendered as a lone opening tag, every other self-closing tag gets
+/// an empty closing tag so that browsers don't nest its siblings inside it.
+const VOID_ELEMENTS: &[&str] = &[
+    "area", "base", "br", "col", "embed", "hr", "img", "input", "link", "meta", "param", "source",
+    "track", "wbr",
+];
+
+/// Renders markup, which can be any number of sibling items, into an HTML
+/// `String`.
+///
+/// ```ignore
+/// let name = "world";
+/// let page: String = html!(<p class="greeting">"Hello, " {name}</p>);
+/// ```
+///
+/// Attribute values and content blocks are rendered through
+/// `rust_jsx::render::Render`, so the crate using the macro needs to depend on
+/// `rust_jsx` as well. Their text is escaped for where it ends up: as HTML
+/// text, as a double-quoted attribute value, as a URL in attributes like
+/// `href` and `src`, where values with `javascript:` and other unsafe schemes
+/// are dropped, as a complete, quoted JavaScript string inside `<script>`, or
+/// as the inside of a CSS string inside `<style>`.
+///
+/// Literals are part of the template, so they're trusted: the ones inside
+/// `<script>` and `<style>` are the code itself and aren't escaped, and URLs
+/// written as literals are kept whatever their scheme. To render markup that
+/// is already escaped as it is, use `rust_jsx::render::Raw`.
+///
+/// The `String` is allocated up front with room for the static markup, and
+/// grows by the `Render::size_hint` of each value before it's rendered.
+///
+/// Content blocks holding control flow whose bodies are markup, like
+/// `{if cond { <a /> } else { <b /> }}` or `{for x in xs { <li>{x}</li> }}`,
+/// expand to the same control flow around the rendering code, and
+/// `{let x = y;}` binds `x` for the siblings after it.
+///
+/// A spread attribute, `{..attributes}`, takes anything that iterates over
+/// `(name, value)` pairs, where the names implement `Display` and the values
+/// implement `Render`. Attributes are rendered in order, and an attribute
+/// replaces any earlier one with the same name. Names that aren't valid
+/// attribute names, like ones with spaces or `=` in them, are left out.
+///
+/// A component tag, like `<ui::Button kind="primary">"Save"</ui::Button>`,
+/// builds its type with a struct literal and renders it through its `Render`
+/// implementation. Attributes become fields, a boolean attribute sets its
+/// field to `true` and a spread fills in the remaining fields with struct
+/// update syntax. The children, if the tag isn't self-closing, are
+/// rendered into a `children: String` field, which holds markup that is
+/// already escaped:
+///
+/// ```ignore
+/// ui::Button { kind: "primary", children: String::from("Save") }
+/// ```
+#[proc_macro]
+pub fn html(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let items = match rust_jsx::parse_many(input.into()) {
+        Ok(items) => items,
+        Err(error) => return error.to_compile_error().into(),
+    };
+
+    let mut body = Code::new();
+    render_items(&items, Context::Text, &mut body);
+
+    let body = render_to_string(body);
+    let output = quote!({
+        fn __render<T: ::rust_jsx::render::Render + ?Sized>(
+            out: &mut ::rust_jsx::render::Output,
+            context: ::rust_jsx::render::Context,
+            value: &T,
+        ) {
+            out.reserve(value.size_hint());
+            out.with_context(context, |out| value.render(out))
+                .expect("a Render implementation returned an error unexpectedly");
+        }
+
+        fn __render_attribute<T: ::rust_jsx::render::Render + ?Sized>(
+            name: &str,
+            value: &T,
+        ) -> ::std::string::String {
+            let mut html = ::std::string::String::new();
+            __render(
+                &mut ::rust_jsx::render::Output::for_string(&mut html),
+                ::rust_jsx::render::attribute_context(name),
+                value,
+            );
+            html
+        }
+
+        fn __set_attribute(
+            attributes: &mut ::std::vec::Vec<(
+                ::std::string::String,
+                ::std::option::Option<::std::string::String>,
+            )>,
+            name: ::std::string::String,
+            value: ::std::option::Option<::std::string::String>,
+        ) {
+            match attributes.iter_mut().find(|attribute| attribute.0 == name) {
+                Some(attribute) => attribute.1 = value,
+                None => attributes.push((name, value)),
+            }
+        }
+
+        #body
+    });
+
+    output.into()
+}
+
+/// Rendering code that is being generated.
+///
+/// Markup that is known at compile time, like tags, literal attributes and
+/// literal content, isn't written out right away but merged with the markup
+/// after it, so that it ends up in a single `&'static str` that's written
+/// with one call. Only code that runs at runtime, like an expression or
+/// control flow, ends a run of markup.
+struct Code {
+    tokens: TokenStream,
+    markup: String,
+
+    /// The length of the markup that's written whenever the code runs, which
+    /// leaves out markup inside control flow. The output is allocated with
+    /// room for it up front, and grows by the `Render::size_hint` of each
+    /// value before it's rendered.
+    static_len: usize,
+}
+
+impl Code {
+    fn new() -> Code {
+        Code {
+            tokens: TokenStream::new(),
+            markup: String::new(),
+            static_len: 0,
+        }
+    }
+
+    /// Appends markup, which is written out along with any markup right
+    /// before or after it.
+    fn push_markup(&mut self, markup: &str) {
+        self.markup.push_str(markup);
+        self.static_len += markup.len();
+    }
+
+    /// Appends code that runs at runtime, after the markup before it.
+    fn push_code(&mut self, code: TokenStream) {
+        self.flush();
+        self.tokens.extend(code);
+    }
+
+    /// Appends code rendering `value` in `context`.
+    fn push_value(&mut self, value: TokenStream, context: TokenStream) {
+        self.push_code(quote!(__render(&mut __output, #context, &(#value));));
+    }
+
+    fn flush(&mut self) {
+        if !self.markup.is_empty() {
+            let markup = mem::take(&mut self.markup);
+            self.tokens
+                .extend(quote!(__output.write_markup(#markup).unwrap();));
+        }
+    }
+
+    fn into_tokens(mut self) -> TokenStream {
+        self.flush();
+        self.tokens
+    }
+}
+
+/// Wraps rendering code in a block that runs it on a new `String` and
+/// evaluates to that `String`.
+fn render_to_string(body: Code) -> TokenStream {
+    // Markup that's all static doesn't need rendering at all.
+    if body.tokens.is_empty() {
+        let markup = &body.markup;
+        return quote!(::std::string::String::from(#markup));
+    }
+
+    let static_len = body.static_len;
+    let body = body.into_tokens();
+
+    quote!({
+        let mut __html = ::std::string::String::with_capacity(#static_len);
+        {
+            let mut __output = ::rust_jsx::render::Output::for_string(&mut __html);
+            #body
+        }
+        __html
+    })
+}
+
+/// Renders an item whose text is in `context`, which is only something other
+/// than `Context::Text` in the children of a `<script>` or `<style>`.
+fn render_item(item: &SnaxItem, context: Context, out: &mut Code) {
+    match item {
+        SnaxItem::Tag(tag) => render_tag(tag, out),
+        SnaxItem::SelfClosingTag(tag) => render_self_closing_tag(tag, out),
+        SnaxItem::Content(content) => render_value(content, context, out),
+        SnaxItem::Fragment(children) => render_items(children, context, out),
+        SnaxItem::If {
+            condition,
+            then,
+            else_,
+        } => {
+            let mut then_body = Code::new();
+            render_items(then, context, &mut then_body);
+            let then_body = then_body.into_tokens();
+
+            out.push_code(quote!(if #condition { #then_body }));
+
+            match else_ {
+                Some(SnaxElse::If(else_if)) => {
+                    out.push_code(quote!(else));
+                    render_item(else_if, context, out);
+                }
+                Some(SnaxElse::Block(else_)) => {
+                    let mut else_body = Code::new();
+                    render_items(else_, context, &mut else_body);
+                    let else_body = else_body.into_tokens();
+
+                    out.push_code(quote!(else { #else_body }));
+                }
+                None => {}
+            }
+        }
+        SnaxItem::For {
+            pattern,
+            iterable,
+            body,
+        } => {
+            let mut loop_body = Code::new();
+            render_items(body, context, &mut loop_body);
+            let loop_body = loop_body.into_tokens();
+
+            out.push_code(quote!(for #pattern in #iterable { #loop_body }));
+        }
+        SnaxItem::Match { scrutinee, arms } => {
+            let mut match_arms = TokenStream::new();
+
+            for arm in arms {
+                let pattern = &arm.pattern;
+                let guard = arm.guard.as_ref().map(|guard| quote!(if #guard));
+
+                let mut arm_body = Code::new();
+                render_items(&arm.body, context, &mut arm_body);
+                let arm_body = arm_body.into_tokens();
+
+                match_arms.extend(quote!(#pattern #guard => { #arm_body }));
+            }
+
+            out.push_code(quote!(match #scrutinee { #match_arms }));
+        }
+        SnaxItem::Let { pattern, init } => out.push_code(quote!(let #pattern = #init;)),
+        SnaxItem::Doctype { name } => render_static(&format!("<!DOCTYPE {}>", name), out),
+        SnaxItem::Comment(content) => {
+            // Escaping the content keeps a `-->` in it from ending the comment
+            // early.
+            render_static("<!-- ", out);
+            render_value(content, Context::Text, out);
+            render_static(" -->", out);
+        }
+    }
+}
+
+fn render_items(items: &[SnaxItem], context: Context, out: &mut Code) {
+    let has_let = items
+        .iter()
+        .any(|item| matches!(item, SnaxItem::Let { .. }));
+
+    // Bindings only reach the siblings after them, so they get a block of
+    // their own instead of leaking into whatever comes after the parent.
+    if has_let {
+        let mut body = Code::new();
+        for item in items {
+            render_item(item, context, &mut body);
+        }
+        let static_len = body.static_len;
+        let body = body.into_tokens();
+
+        out.push_code(quote!({ #body }));
+        out.static_len += static_len;
+    } else {
+        for item in items {
+            render_item(item, context, out);
+        }
+    }
+}
+
+fn render_tag(tag: &SnaxTag, out: &mut Code) {
+    if let SnaxTagName::Path(path) = &tag.name {
+        render_component(path, &tag.attributes, Some(&tag.children), out);
+        return;
+    }
+
+    let name = tag.name.to_string();
+
+    render_static(&format!("<{}", name), out);
+    render_attributes(&tag.attributes, out);
+    render_static(">", out);
+
+    // The text inside `<script>` and `<style>` isn't HTML, so it's escaped
+    // differently.
+    let context = if name.eq_ignore_ascii_case("script") {
+        Context::Script
+    } else if name.eq_ignore_ascii_case("style") {
+        Context::Style
+    } else {
+        Context::Text
+    };
+    render_items(&tag.children, context, out);
+
+    render_static(&format!("</{}>", name), out);
+}
+
+fn render_self_closing_tag(tag: &SnaxSelfClosingTag, out: &mut Code) {
+    if let SnaxTagName::Path(path) = &tag.name {
+        render_component(path, &tag.attributes, None, out);
+        return;
+    }
+
+    let name = tag.name.to_string();
+
+    render_static(&format!("<{}", name), out);
+    render_attributes(&tag.attributes, out);
+
+    if VOID_ELEMENTS.contains(&name.as_str()) {
+        render_static(">", out);
+    } else {
+        render_static(&format!("></{}>", name), out);
+    }
+}
+
+/// Renders a component by building it from its attributes and children, see
+/// the docs of `html!`.
+fn render_component(
+    path: &SnaxPath,
+    attributes: &[SnaxAttribute],
+    children: Option<&[SnaxItem]>,
+    out: &mut Code,
+) {
+    let mut fields = TokenStream::new();
+    let mut spread = None;
+
+    for attribute in attributes {
+        match attribute {
+            SnaxAttribute::Simple { name, value } => match field_name(name) {
+                Some(field) => fields.extend(quote!(#field: #value,)),
+                None => return render_error(name.span(), "expected a field name", out),
+            },
+            SnaxAttribute::Boolean { name } => match field_name(name) {
+                Some(field) => fields.extend(quote!(#field: true,)),
+                None => return render_error(name.span(), "expected a field name", out),
+            },
+            SnaxAttribute::Shorthand { name } => fields.extend(quote!(#name,)),
+            SnaxAttribute::Spread(props) => {
+                if spread.is_some() {
+                    let span = props.clone().into_iter().next().unwrap().span();
+                    return render_error(span, "a component can only have one spread", out);
+                }
+                spread = Some(props);
+            }
+        }
+    }
+
+    if let Some(children) = children {
+        let mut body = Code::new();
+        render_items(children, Context::Text, &mut body);
+
+        let children = render_to_string(body);
+        fields.extend(quote!(children: #children,));
+    }
+
+    let spread = spread.map(|props| quote!(..(#props)));
+
+    let mut path_tokens = TokenStream::new();
+    for (i, segment) in path.segments.iter().enumerate() {
+        if i > 0 {
+            path_tokens.extend(quote!(::));
+        }
+
+        let ident = &segment.ident;
+        path_tokens.extend(quote!(#ident));
+
+        // Generic arguments need a turbofish in expression position.
+        if let Some(arguments) = &segment.arguments {
+            path_tokens.extend(quote!(::<#arguments>));
+        }
+    }
+
+    out.push_value(
+        quote!(#path_tokens { #fields #spread }),
+        quote!(::rust_jsx::render::Context::Text),
+    );
+}
+
+/// The field a component attribute sets, which needs to be a plain
+/// identifier.
+fn field_name(name: &SnaxName) -> Option<&Ident> {
+    match (&name.namespace, name.local.as_slice()) {
+        (None, [field]) => Some(field),
+        _ => None,
+    }
+}
+
+fn render_error(span: Span, message: &str, out: &mut Code) {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+
+    out.push_code(quote_spanned!(span=> compile_error!(#message);));
+}
+
+fn render_attributes(attributes: &[SnaxAttribute], out: &mut Code) {
+    let has_spread = attributes
+        .iter()
+        .any(|attribute| matches!(attribute, SnaxAttribute::Spread(_)));
+
+    if has_spread {
+        render_dynamic_attributes(attributes, out);
+        return;
+    }
+
+    for attribute in attributes {
+        match attribute {
+            SnaxAttribute::Simple { name, value } => render_simple_attribute(name, value, out),
+            SnaxAttribute::Shorthand { name } => {
+                render_simple_attribute(name, &TokenTree::Ident(name.clone()), out)
+            }
+            SnaxAttribute::Boolean { name } => render_static(&format!(" {}", name), out),
+            SnaxAttribute::Spread(_) => unreachable!(),
+        }
+    }
+}
+
+fn render_simple_attribute(name: &dyn fmt::Display, value: &TokenTree, out: &mut Code) {
+    let name = name.to_string();
+
+    render_static(&format!(" {}=\"", name), out);
+    render_value(value, attribute_context(&name), out);
+    render_static("\"", out);
+}
+
+/// Renders attributes when some of them come from a spread, whose names
+/// aren't known until runtime. The attributes are collected into a list first
+/// so that a later attribute replaces an earlier one with the same name.
+fn render_dynamic_attributes(attributes: &[SnaxAttribute], out: &mut Code) {
+    let mut body = TokenStream::new();
+
+    for attribute in attributes {
+        body.extend(match attribute {
+            SnaxAttribute::Simple { name, value } => {
+                let name = name.to_string();
+                quote! {
+                    __set_attribute(
+                        &mut __attributes,
+                        #name.to_owned(),
+                        Some(__render_attribute(#name, &#value)),
+                    );
+                }
+            }
+            SnaxAttribute::Shorthand { name } => {
+                let value = name;
+                let name = name.to_string();
+                quote! {
+                    __set_attribute(
+                        &mut __attributes,
+                        #name.to_owned(),
+                        Some(__render_attribute(#name, &#value)),
+                    );
+                }
+            }
+            SnaxAttribute::Boolean { name } => {
+                let name = name.to_string();
+                quote!(__set_attribute(&mut __attributes, #name.to_owned(), None);)
+            }
+            SnaxAttribute::Spread(spread) => quote! {
+                for (name, value) in (#spread) {
+                    let name = ::std::string::ToString::to_string(&name);
+                    if !::rust_jsx::render::is_attribute_name(&name) {
+                        continue;
+                    }
+
+                    let value = __render_attribute(&name, &value);
+                    __set_attribute(&mut __attributes, name, Some(value));
+                }
+            },
+        });
+    }
+
+    out.push_code(quote!({
+        let mut __attributes = ::std::vec::Vec::new();
+        #body
+
+        // The names were checked and the values were escaped when they were
+        // rendered.
+        for (name, value) in __attributes {
+            __output.write_markup(" ").unwrap();
+            __output.write_markup(&name).unwrap();
+
+            if let Some(value) = value {
+                __output.write_markup("=\"").unwrap();
+                __output.write_markup(&value).unwrap();
+                __output.write_markup("\"").unwrap();
+            }
+        }
+    }));
+}
+
+/// Appends markup that is known at compile time, verbatim.
+fn render_static(text: &str, out: &mut Code) {
+    out.push_markup(text);
+}
+
+/// Appends a literal or block expression through its `Render`
+/// implementation, escaped for `context`.
+fn render_value(value: &TokenTree, context: Context, out: &mut Code) {
+    let literal = match value {
+        TokenTree::Literal(literal) => Some(literal),
+        _ => None,
+    };
+
+    // Literals are part of the template, like the code in a `<script>`, so a
+    // URL written in one is trusted and only needs escaping.
+    let context = match context {
+        Context::Url if literal.is_some() => Context::Attribute,
+        context => context,
+    };
+
+    // Literals, and blocks holding nothing but one, are rendered at compile
+    // time, so that they're merged with the markup around them.
+    match literal {
+        Some(literal) => {
+            if let Some(text) = literal_text(literal) {
+                match context {
+                    // Literals in a `<script>` or `<style>` are code written
+                    // along with the template, so they're written out as they
+                    // are.
+                    Context::Script | Context::Style => out.push_markup(&text),
+                    context => push_text(&text, context, out),
+                }
+                return;
+            }
+        }
+        None => {
+            if let Some(text) = block_text(value) {
+                push_text(&text, context, out);
+                return;
+            }
+        }
+    }
+
+    let context = match context {
+        // Literals in a `<script>` or `<style>` are code written along with
+        // the template, so they're written out as they are.
+        Context::Script | Context::Style if literal.is_some() => {
+            out.push_code(quote! {
+                __output.write_markup(&::std::string::ToString::to_string(&#value)).unwrap();
+            });
+            return;
+        }
+        Context::Text => quote!(::rust_jsx::render::Context::Text),
+        Context::Attribute => quote!(::rust_jsx::render::Context::Attribute),
+        Context::Url => quote!(::rust_jsx::render::Context::Url),
+        Context::Script => quote!(::rust_jsx::render::Context::Script),
+        Context::Style => quote!(::rust_jsx::render::Context::Style),
+    };
+
+    out.push_value(value.into_token_stream(), context);
+}
+
+/// Escapes `text` for `context` at compile time and merges it with the
+/// markup.
+fn push_text(text: &str, context: Context, out: &mut Code) {
+    let mut markup = String::new();
+    Output::new(&mut markup)
+        .with_context(context, |output| output.write_text(text))
+        .unwrap();
+
+    out.push_markup(&markup);
+}
+
+/// The text a block like `{5}` or `{true}` renders as, or `None` if it holds
+/// anything but a single literal or `bool`.
+fn block_text(value: &TokenTree) -> Option<String> {
+    let group = match value {
+        TokenTree::Group(group) if group.delimiter() == Delimiter::Brace => group,
+        _ => return None,
+    };
+
+    let mut tokens = group.stream().into_iter();
+    let text = match tokens.next()? {
+        TokenTree::Literal(literal) => literal_text(&literal)?,
+        TokenTree::Ident(ident) if ident == "true" || ident == "false" => ident.to_string(),
+        _ => return None,
+    };
+
+    match tokens.next() {
+        None => Some(text),
+        Some(_) => None,
+    }
+}
+
+/// The text a literal renders as, like `a\tb` for `"a\tb"`, `a` for `'a'` or
+/// `255` for `0xff_u8`, or `None` for byte strings and numbers that don't fit
+/// their type.
+fn literal_text(literal: &Literal) -> Option<String> {
+    let source = literal.to_string();
+
+    if let Some(raw) = source.strip_prefix('r') {
+        let hashes = raw.find('"')?;
+        return raw
+            .get(hashes + 1..raw.len() - hashes - 1)
+            .map(String::from);
+    }
+
+    if let Some(quoted) = source.strip_prefix('"') {
+        return unescape(quoted.strip_suffix('"')?);
+    }
+
+    if let Some(quoted) = source.strip_prefix('\'') {
+        return unescape(quoted.strip_suffix('\'')?);
+    }
+
+    number_text(&source)
+}
+
+/// The decimal text of an integer or float literal, formatted the way its
+/// type's `Display` would.
+fn number_text(source: &str) -> Option<String> {
+    const INTEGERS: [(&str, u128); 12] = [
+        ("i8", i8::MAX as u128),
+        ("i16", i16::MAX as u128),
+        ("i32", i32::MAX as u128),
+        ("i64", i64::MAX as u128),
+        ("i128", i128::MAX as u128),
+        ("isize", isize::MAX as u128),
+        ("u8", u8::MAX as u128),
+        ("u16", u16::MAX as u128),
+        ("u32", u32::MAX as u128),
+        ("u64", u64::MAX as u128),
+        ("u128", u128::MAX),
+        ("usize", usize::MAX as u128),
+    ];
+
+    let source = source.replace('_', "");
+    let (radix, digits) = match source.get(..2) {
+        Some("0x") => (16, &source[2..]),
+        Some("0o") => (8, &source[2..]),
+        Some("0b") => (2, &source[2..]),
+        _ => (10, &source[..]),
+    };
+
+    let is_float = radix == 10
+        && (digits.contains(['.', 'e', 'E']) || digits.ends_with("f32") || digits.ends_with("f64"));
+
+    if is_float {
+        return match digits.strip_suffix("f32") {
+            Some(digits) => digits.parse::<f32>().ok().map(|value| value.to_string()),
+            None => digits
+                .strip_suffix("f64")
+                .unwrap_or(digits)
+                .parse::<f64>()
+                .ok()
+                .map(|value| value.to_string()),
+        };
+    }
+
+    // An integer without a suffix is an `i32`, unless the code around it says
+    // otherwise, which is left to the compiler.
+    let (digits, max) = INTEGERS
+        .iter()
+        .find_map(|&(suffix, max)| Some((digits.strip_suffix(suffix)?, max)))
+        .unwrap_or((digits, i32::MAX as u128));
+
+    let value = u128::from_str_radix(digits, radix).ok()?;
+    if value > max {
+        return None;
+    }
+
+    Some(value.to_string())
+}
+
+/// The value of the text between the quotes of a string or char literal.
+fn unescape(source: &str) -> Option<String> {
+    let mut value = String::new();
+    let mut chars = source.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+
+        match chars.next()? {
+            'n' => value.push('\n'),
+            'r' => value.push('\r'),
+            't' => value.push('\t'),
+            '0' => value.push('\0'),
+            '\\' => value.push('\\'),
+            '\'' => value.push('\''),
+            '"' => value.push('"'),
+            'x' => {
+                let digits: String = chars.by_ref().take(2).collect();
+                value.push(char::from(u8::from_str_radix(&digits, 16).ok()?));
+            }
+            'u' => {
+                let digits: String = chars
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|&c| c != '}')
+                    .filter(|&c| c != '_')
+                    .collect();
+                value.push(std::char::from_u32(u32::from_str_radix(&digits, 16).ok()?)?);
+            }
+            // A backslash at the end of a line skips the line break and the
+            // whitespace after it.
+            '\n' => {
+                while chars.peek().is_some_and(|c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            _ => return None,
+        }
+    }
+
+    Some(value)
+}
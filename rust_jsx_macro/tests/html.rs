@@ -0,0 +1,527 @@
+use std::cell::{Cell, RefCell};
+use std::fmt::{self, Write};
+
+use rust_jsx::render::{self, Output, Render};
+use rust_jsx_macro::html;
+
+#[test]
+fn just_string() {
+    let output: String = html!("hello");
+
+    assert_eq!(output, "hello");
+}
+
+#[test]
+fn empty_div() {
+    let output = html!(<div></div>);
+
+    assert_eq!(output, "<div></div>");
+}
+
+#[test]
+fn self_closing_div() {
+    let output = html!(<div />);
+
+    assert_eq!(output, "<div></div>");
+}
+
+#[test]
+fn void_element() {
+    let output = html!(<br />);
+
+    assert_eq!(output, "<br>");
+}
+
+#[test]
+fn literal_attributes() {
+    let output = html!(<div foo="bar" baz="qux"></div>);
+
+    assert_eq!(output, r#"<div foo="bar" baz="qux"></div>"#);
+}
+
+#[test]
+fn block_attribute() {
+    let output = html!(<label sum={ 5 + 5 } />);
+
+    assert_eq!(output, r#"<label sum="10"></label>"#);
+}
+
+#[test]
+fn nested_content() {
+    let name = "world";
+    let output = html!(
+        <p>
+            <span>"Hello, "</span>
+            {name}
+        </p>
+    );
+
+    assert_eq!(output, "<p><span>Hello, </span>world</p>");
+}
+
+#[test]
+fn escapes_content() {
+    let input = "<script>alert(\"hi\")</script>";
+    let output = html!(<div title={input}>{input}</div>);
+
+    assert_eq!(
+        output,
+        "<div title=\"&lt;script&gt;alert(&quot;hi&quot;)&lt;/script&gt;\">\
+         &lt;script&gt;alert(&quot;hi&quot;)&lt;/script&gt;</div>"
+    );
+}
+
+#[test]
+fn fragment() {
+    let output = html!(<>"Hello, " <b>"world"</b></>);
+
+    assert_eq!(output, "Hello, <b>world</b>");
+}
+
+#[test]
+fn hyphenated_names() {
+    let output = html!(<my-widget data-id="3" aria-label="x" />);
+
+    assert_eq!(
+        output,
+        r#"<my-widget data-id="3" aria-label="x"></my-widget>"#
+    );
+}
+
+#[test]
+fn namespaced_names() {
+    let output = html!(
+        <svg xmlns:xlink="http://www.w3.org/1999/xlink">
+            <use xlink:href="#icon" />
+        </svg>
+    );
+
+    assert_eq!(
+        output,
+        r##"<svg xmlns:xlink="http://www.w3.org/1999/xlink"><use xlink:href="#icon"></use></svg>"##
+    );
+}
+
+#[test]
+fn boolean_attributes() {
+    let output = html!(<input disabled type="checkbox" checked />);
+
+    assert_eq!(output, r#"<input disabled type="checkbox" checked>"#);
+}
+
+#[test]
+fn spread_attributes() {
+    let props = vec![("id", "from-spread"), ("title", "Hello")];
+    let output = html!(<div id="first" {..props} title="last" />);
+
+    assert_eq!(output, r#"<div id="from-spread" title="last"></div>"#);
+}
+
+#[test]
+fn invalid_spread_names_are_dropped() {
+    let props = vec![
+        ("x onmouseover=alert(1) y", "v"),
+        ("a\"b", "v"),
+        ("/>", "v"),
+        ("", "v"),
+        ("data-ok", "v"),
+    ];
+    let output = html!(<div {..props} />);
+
+    assert_eq!(output, r#"<div data-ok="v"></div>"#);
+}
+
+#[test]
+fn shorthand_attributes() {
+    let src = "/logo.png";
+    let alt = "Logo";
+    let output = html!(<img {src} {alt} />);
+
+    assert_eq!(output, r#"<img src="/logo.png" alt="Logo">"#);
+}
+
+#[test]
+fn shorthand_overrides_spread() {
+    let title = "mine";
+    let props = vec![("title", "theirs")];
+    let output = html!(<div {..props} {title} />);
+
+    assert_eq!(output, r#"<div title="mine"></div>"#);
+}
+
+#[test]
+fn many_roots() {
+    let output = html!(<li>"one"</li> <li>"two"</li>);
+
+    assert_eq!(output, "<li>one</li><li>two</li>");
+}
+
+#[test]
+fn if_else() {
+    let render = |count: usize| {
+        html!(
+            <p>
+                {if count == 0 {
+                    <em>"none"</em>
+                } else if count == 1 {
+                    "one"
+                } else {
+                    {count} " items"
+                }}
+            </p>
+        )
+    };
+
+    assert_eq!(render(0), "<p><em>none</em></p>");
+    assert_eq!(render(1), "<p>one</p>");
+    assert_eq!(render(5), "<p>5 items</p>");
+}
+
+#[test]
+fn if_without_else() {
+    let render = |show: bool| html!(<div>{if show { "shown" }}</div>);
+
+    assert_eq!(render(true), "<div>shown</div>");
+    assert_eq!(render(false), "<div></div>");
+}
+
+#[test]
+fn for_loop() {
+    let items = vec!["one", "two", "<three>"];
+    let output = html!(<ul>{for item in &items { <li>{item}</li> }}</ul>);
+
+    assert_eq!(
+        output,
+        "<ul><li>one</li><li>two</li><li>&lt;three&gt;</li></ul>"
+    );
+}
+
+#[test]
+fn nested_loops() {
+    let rows = vec![vec![1, 2], vec![3]];
+    let output = html!(
+        <table>
+            {for row in &rows {
+                <tr>{for cell in row { <td>{cell}</td> }}</tr>
+            }}
+        </table>
+    );
+
+    assert_eq!(
+        output,
+        "<table><tr><td>1</td><td>2</td></tr><tr><td>3</td></tr></table>"
+    );
+}
+
+#[test]
+fn match_arms() {
+    enum State {
+        Loading,
+        Failed(&'static str),
+        Ready(u32),
+    }
+
+    let render = |state: State| {
+        html!(
+            <div>
+                {match state {
+                    State::Loading => <progress />,
+                    State::Failed(error) if error.is_empty() => "Unknown error",
+                    State::Failed(error) => <p class="error">{error}</p>,
+                    State::Ready(count) => { {count} " results" }
+                }}
+            </div>
+        )
+    };
+
+    assert_eq!(render(State::Loading), "<div><progress></progress></div>");
+    assert_eq!(render(State::Failed("")), "<div>Unknown error</div>");
+    assert_eq!(
+        render(State::Failed("Timeout")),
+        r#"<div><p class="error">Timeout</p></div>"#
+    );
+    assert_eq!(render(State::Ready(3)), "<div>3 results</div>");
+}
+
+#[test]
+fn let_binding() {
+    let items = [3, 4];
+    let output = html!(
+        <div>
+            {let total: i32 = items.iter().sum();}
+            <p>{total}</p>
+            <p>{total * 2}</p>
+        </div>
+    );
+
+    assert_eq!(output, "<div><p>7</p><p>14</p></div>");
+}
+
+#[test]
+fn let_binding_is_scoped_to_parent() {
+    let label = "outer";
+    let output = html!(
+        <div>{let label = "inner";} {label}</div>
+        {label}
+    );
+
+    assert_eq!(output, "<div>inner</div>outer");
+}
+
+#[test]
+fn doctype_and_comments() {
+    let note = "a --> b";
+    let output = html!(
+        <!DOCTYPE html>
+        <html>
+            <!-- "static" -->
+            <!-- {note} -->
+        </html>
+    );
+
+    assert_eq!(
+        output,
+        "<!DOCTYPE html><html><!-- static --><!-- a --&gt; b --></html>"
+    );
+}
+
+mod ui {
+    use std::fmt;
+
+    use rust_jsx::render::{Output, Render};
+
+    #[derive(Default)]
+    pub struct Button {
+        pub kind: &'static str,
+        pub disabled: bool,
+        pub children: String,
+    }
+
+    impl Render for Button {
+        fn render(&self, out: &mut Output) -> fmt::Result {
+            out.write_markup("<button class=\"")?;
+            out.write_text(self.kind)?;
+            out.write_markup("\"")?;
+            if self.disabled {
+                out.write_markup(" disabled")?;
+            }
+            out.write_markup(">")?;
+            out.write_markup(&self.children)?;
+            out.write_markup("</button>")
+        }
+    }
+}
+
+struct List<T> {
+    items: Vec<T>,
+}
+
+impl<T: Render> Render for List<T> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        out.write_markup("<ul>")?;
+        for item in &self.items {
+            out.write_markup("<li>")?;
+            item.render(out)?;
+            out.write_markup("</li>")?;
+        }
+        out.write_markup("</ul>")
+    }
+}
+
+#[test]
+fn components() {
+    let kind = "primary";
+    let output = html!(
+        <div>
+            <ui::Button {kind} disabled>"Save " <b>"now"</b></ui::Button>
+            <List<u8> items={vec![1, 2]} />
+        </div>
+    );
+
+    assert_eq!(
+        output,
+        "<div><button class=\"primary\" disabled>Save <b>now</b></button>\
+         <ul><li>1</li><li>2</li></ul></div>"
+    );
+}
+
+#[test]
+fn component_spread() {
+    let output = html!(<ui::Button kind="link" {..Default::default()}>"Back"</ui::Button>);
+
+    assert_eq!(output, r#"<button class="link">Back</button>"#);
+}
+
+#[test]
+fn render_values() {
+    let name: Option<&str> = Some("<Tom>");
+    let nobody: Option<&str> = None;
+    let scores = vec![1.5, 2.0];
+    let output = html!(
+        <p>
+            {name} {nobody} {scores}
+            {render::iter(["a", "b"].iter().map(|letter| (letter, ',')))}
+            {render::from_fn(|out| out.write_markup("<br>"))}
+        </p>
+    );
+
+    assert_eq!(output, "<p>&lt;Tom&gt;1.52a,b,<br></p>");
+}
+
+#[test]
+fn escaping_contexts() {
+    let title = "\"Tom\" & Jerry";
+    let url = "javascript:alert(1)";
+    let output = html!(<a title={title} href={url}>{title}</a>);
+
+    assert_eq!(
+        output,
+        r#"<a title="&quot;Tom&quot; &amp; Jerry" href="about:invalid">&quot;Tom&quot; &amp; Jerry</a>"#
+    );
+
+    let attributes = vec![("src", "/cat.png?size=1&x=2"), ("alt", "<cat>")];
+    let output = html!(<img {..attributes} />);
+
+    assert_eq!(
+        output,
+        r#"<img src="/cat.png?size=1&amp;x=2" alt="&lt;cat&gt;">"#
+    );
+}
+
+#[test]
+fn script_and_style() {
+    let name = "</script>";
+    let code = "alert(document.cookie)";
+    let color = "red;}";
+    let output = html!(
+        <script>"var name = " {name} ", code = " {code} ";"</script>
+        <style>"p { color: " {color} "; }"</style>
+    );
+
+    assert_eq!(
+        output,
+        concat!(
+            r#"<script>var name = "\x3C/script\x3E", code = "alert(document.cookie)";</script>"#,
+            r#"<style>p { color: red\3b \7d ; }</style>"#
+        )
+    );
+}
+
+#[test]
+fn raw_markup() {
+    let icon = render::Raw("<svg></svg>");
+    let output = html!(<button>{icon} {"<svg>"}</button>);
+
+    assert_eq!(output, "<button><svg></svg>&lt;svg&gt;</button>");
+}
+
+#[test]
+fn static_markup() {
+    let output = html!(
+        <ul class="nav" data-x="a \"b\" & c">
+            <li>"Tom\t& \u{e9}\
+                 Jerry"</li>
+            <li>r#"<"raw">"#</li>
+            <a href="javascript:void(0)">"x"</a>
+            <a href="/?a=1&b=2">"y"</a>
+            <script>"if (a < b) {}"</script>
+        </ul>
+    );
+
+    assert_eq!(
+        output,
+        concat!(
+            r#"<ul class="nav" data-x="a &quot;b&quot; &amp; c">"#,
+            "<li>Tom\t&amp; \u{e9}Jerry</li>",
+            "<li>&lt;&quot;raw&quot;&gt;</li>",
+            r#"<a href="javascript:void(0)">x</a>"#,
+            r#"<a href="/?a=1&amp;b=2">y</a>"#,
+            "<script>if (a < b) {}</script>",
+            "</ul>"
+        )
+    );
+}
+
+#[test]
+fn static_literals() {
+    let output = html!(
+        <p width=100 data-ratio=1.50 data-mask=0xff_u8 data-sep='"'>
+            5 ' ' 2.5e3 ' ' {true} ' ' {'<'} ' ' {1_000u32}
+            <script>"let x = " 5 ";"</script>
+        </p>
+    );
+
+    assert_eq!(
+        output,
+        concat!(
+            r#"<p width="100" data-ratio="1.5" data-mask="255" data-sep="&quot;">"#,
+            "5 2500 true &lt; 1000",
+            "<script>let x = 5;</script>",
+            "</p>"
+        )
+    );
+
+    // Everything was known at compile time, so it's a single `&'static str`
+    // and nothing was written at runtime.
+    assert_eq!(output.capacity(), output.len());
+}
+
+#[test]
+fn allocates_up_front() {
+    /// Writes its hint's worth of text, recording how much room it had.
+    struct Hinted<'a> {
+        hints: &'a Cell<usize>,
+        spare: &'a RefCell<Vec<Option<usize>>>,
+    }
+
+    impl Render for Hinted<'_> {
+        fn render(&self, out: &mut Output) -> fmt::Result {
+            self.spare.borrow_mut().push(out.spare_capacity());
+            out.write_text("hinted")
+        }
+
+        fn size_hint(&self) -> usize {
+            self.hints.set(self.hints.get() + 1);
+            6
+        }
+    }
+
+    let hints = Cell::new(0);
+    let spare = RefCell::new(Vec::new());
+    let hinted = || Hinted {
+        hints: &hints,
+        spare: &spare,
+    };
+
+    // The markup around the values doesn't leave room for them, so each one
+    // only fits because its hint was reserved before it was rendered.
+    let output = html!(<p class="name">{hinted()} {hinted()} {render::Raw("<br>")}</p>);
+
+    assert_eq!(output, r#"<p class="name">hintedhinted<br></p>"#);
+    assert_eq!(hints.get(), 2);
+    for spare in spare.take() {
+        assert!(spare >= Some(6), "{:?}", spare);
+    }
+
+    // The static markup is allocated before anything is rendered.
+    let output = html!(<>{hinted()}<p class="name"></p></>);
+
+    assert_eq!(output, r#"hinted<p class="name"></p>"#);
+    assert!(spare.take()[0] >= Some(r#"<p class="name"></p>"#.len()));
+}
+
+#[test]
+fn values_are_evaluated_where_they_are_rendered() {
+    let mut n = 0;
+    let output = html!(<p>{&n}</p> <p>{{ n += 1; n }}</p>);
+
+    assert_eq!(output, "<p>0</p><p>1</p>");
+
+    let c = Cell::new(0);
+    let output = html!(
+        <p>{render::from_fn(|out| write!(out, "{}", c.get()))}</p>
+        <p>{{ c.set(5); "" }} {String::from("a").as_str()}</p>
+    );
+
+    assert_eq!(output, "<p>0</p><p>a</p>");
+}
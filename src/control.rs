@@ -0,0 +1,292 @@
+//! Control flow inside content blocks, like `{if cond { <a /> }}`,
+//! `{for x in xs { <li>{x}</li> }}` or `{match x { A => <a />, B => <b /> }}`,
+//! along with `{let x = y;}` bindings.
+//!
+//! Rust expressions and markup share a lot of syntax, so a block is only
+//! treated as control flow if all of its bodies parse as markup. Anything else
+//! is left alone as a plain `SnaxItem::Content` expression.
+
+use std::iter::Peekable;
+
+use proc_macro2::{Delimiter, Group, Spacing, TokenStream, TokenTree};
+
+use crate::{parse_item, parse_siblings, ParseError, SnaxElse, SnaxItem, SnaxMatchArm};
+
+/// Turns a content token into a `SnaxItem`, recognizing control flow in
+/// braced blocks.
+pub fn parse_content(content: TokenTree) -> Result<SnaxItem, ParseError> {
+    if let TokenTree::Group(group) = &content {
+        if group.delimiter() == Delimiter::Brace {
+            let mut tokens = group.stream().into_iter();
+
+            let item = match tokens.next() {
+                Some(TokenTree::Ident(ref keyword)) if keyword == "if" => parse_if(tokens)?,
+                Some(TokenTree::Ident(ref keyword)) if keyword == "for" => parse_for(tokens)?,
+                Some(TokenTree::Ident(ref keyword)) if keyword == "match" => parse_match(tokens)?,
+                Some(TokenTree::Ident(ref keyword)) if keyword == "let" => parse_let(tokens),
+                _ => None,
+            };
+
+            if let Some(item) = item {
+                return Ok(item);
+            }
+        }
+    }
+
+    Ok(SnaxItem::Content(content))
+}
+
+/// Parses what comes after the `if` keyword, up to the end of the block.
+fn parse_if(mut tokens: impl Iterator<Item = TokenTree>) -> Result<Option<SnaxItem>, ParseError> {
+    // Struct literals aren't allowed in conditions, so the first braced group
+    // is the body.
+    let mut condition = TokenStream::new();
+    let then = loop {
+        match tokens.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => break group,
+            Some(token) => condition.extend(Some(token)),
+            None => return Ok(None),
+        }
+    };
+
+    if condition.is_empty() {
+        return Ok(None);
+    }
+
+    let then = match parse_body(&then)? {
+        Some(then) => then,
+        None => return Ok(None),
+    };
+
+    let else_ = match tokens.next() {
+        None => None,
+        Some(TokenTree::Ident(ref keyword)) if keyword == "else" => match tokens.next() {
+            Some(TokenTree::Ident(ref keyword)) if keyword == "if" => match parse_if(tokens)? {
+                Some(else_if) => Some(SnaxElse::If(Box::new(else_if))),
+                None => return Ok(None),
+            },
+            Some(TokenTree::Group(ref group)) if group.delimiter() == Delimiter::Brace => {
+                match (parse_body(group)?, tokens.next()) {
+                    (Some(else_), None) => Some(SnaxElse::Block(else_)),
+                    _ => return Ok(None),
+                }
+            }
+            _ => return Ok(None),
+        },
+        Some(_) => return Ok(None),
+    };
+
+    Ok(Some(SnaxItem::If {
+        condition,
+        then,
+        else_,
+    }))
+}
+
+/// Parses what comes after the `for` keyword, up to the end of the block.
+fn parse_for(mut tokens: impl Iterator<Item = TokenTree>) -> Result<Option<SnaxItem>, ParseError> {
+    let mut pattern = TokenStream::new();
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Ident(ref keyword)) if keyword == "in" => break,
+            Some(token) => pattern.extend(Some(token)),
+            None => return Ok(None),
+        }
+    }
+
+    // Like conditions, iterables can't contain struct literals, so the first
+    // braced group is the body.
+    let mut iterable = TokenStream::new();
+    let body = loop {
+        match tokens.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => break group,
+            Some(token) => iterable.extend(Some(token)),
+            None => return Ok(None),
+        }
+    };
+
+    if pattern.is_empty() || iterable.is_empty() || tokens.next().is_some() {
+        return Ok(None);
+    }
+
+    Ok(parse_body(&body)?.map(|body| SnaxItem::For {
+        pattern,
+        iterable,
+        body,
+    }))
+}
+
+/// Parses what comes after the `match` keyword, up to the end of the block.
+fn parse_match(
+    mut tokens: impl Iterator<Item = TokenTree>,
+) -> Result<Option<SnaxItem>, ParseError> {
+    let mut scrutinee = TokenStream::new();
+    let arms = loop {
+        match tokens.next() {
+            Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => break group,
+            Some(token) => scrutinee.extend(Some(token)),
+            None => return Ok(None),
+        }
+    };
+
+    if scrutinee.is_empty() || tokens.next().is_some() {
+        return Ok(None);
+    }
+
+    let mut tokens = arms.stream().into_iter().peekable();
+    let mut arms = Vec::new();
+    while tokens.peek().is_some() {
+        match parse_match_arm(&mut tokens)? {
+            Some(arm) => arms.push(arm),
+            None => return Ok(None),
+        }
+    }
+
+    if arms.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(SnaxItem::Match { scrutinee, arms }))
+}
+
+/// Parses one arm of a `match`, along with the comma after it.
+fn parse_match_arm(
+    tokens: &mut Peekable<impl Iterator<Item = TokenTree>>,
+) -> Result<Option<SnaxMatchArm>, ParseError> {
+    let mut pattern = TokenStream::new();
+    let mut guard: Option<TokenStream> = None;
+
+    loop {
+        match tokens.next() {
+            Some(TokenTree::Punct(ref punct))
+                if punct.as_char() == '='
+                    && punct.spacing() == Spacing::Joint
+                    && is_punct(tokens.peek(), '>') =>
+            {
+                tokens.next();
+                break;
+            }
+            Some(TokenTree::Ident(ref keyword)) if keyword == "if" && guard.is_none() => {
+                guard = Some(TokenStream::new());
+            }
+            Some(token) => match &mut guard {
+                Some(guard) => guard.extend(Some(token)),
+                None => pattern.extend(Some(token)),
+            },
+            None => return Ok(None),
+        }
+    }
+
+    if pattern.is_empty() || guard.as_ref().is_some_and(TokenStream::is_empty) {
+        return Ok(None);
+    }
+
+    let body = match tokens.peek() {
+        Some(TokenTree::Group(group)) if group.delimiter() == Delimiter::Brace => {
+            let group = group.clone();
+            tokens.next();
+
+            if is_punct(tokens.peek(), ',') {
+                tokens.next();
+            }
+
+            match parse_body(&group)? {
+                Some(body) => body,
+                None => return Ok(None),
+            }
+        }
+        first => {
+            let starts_with_tag = is_punct(first, '<');
+            let mut body = Vec::new();
+
+            while tokens.peek().is_some() && !is_punct(tokens.peek(), ',') {
+                match parse_item(tokens, false) {
+                    Ok(item) => body.push(item),
+                    Err(error) if starts_with_tag => return Err(error),
+                    Err(_) => return Ok(None),
+                }
+            }
+            tokens.next();
+
+            if body.is_empty() {
+                return Ok(None);
+            }
+            body
+        }
+    };
+
+    Ok(Some(SnaxMatchArm {
+        pattern,
+        guard,
+        body,
+    }))
+}
+
+/// Parses what comes after the `let` keyword, which has to be the whole
+/// block apart from an optional trailing semicolon.
+fn parse_let(tokens: impl Iterator<Item = TokenTree>) -> Option<SnaxItem> {
+    let mut tokens = tokens.peekable();
+    let mut pattern = TokenStream::new();
+    let mut previous_joint = false;
+
+    // The `=` of the statement is the first one that isn't part of a longer
+    // operator, like the end of `<=` or the start of `==`.
+    loop {
+        match tokens.next()? {
+            TokenTree::Punct(ref punct)
+                if punct.as_char() == '='
+                    && punct.spacing() == Spacing::Alone
+                    && !previous_joint =>
+            {
+                break;
+            }
+            token => {
+                previous_joint = match &token {
+                    TokenTree::Punct(punct) => punct.spacing() == Spacing::Joint,
+                    _ => false,
+                };
+                pattern.extend(Some(token));
+            }
+        }
+    }
+
+    let mut init = TokenStream::new();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(ref punct) if punct.as_char() == ';' => {
+                if tokens.peek().is_some() {
+                    return None;
+                }
+            }
+            token => init.extend(Some(token)),
+        }
+    }
+
+    if pattern.is_empty() || init.is_empty() {
+        return None;
+    }
+
+    Some(SnaxItem::Let { pattern, init })
+}
+
+fn is_punct(token: Option<&TokenTree>, c: char) -> bool {
+    match token {
+        Some(TokenTree::Punct(punct)) => punct.as_char() == c,
+        _ => false,
+    }
+}
+
+/// Parses the inside of a braced body as markup.
+///
+/// Returns `None` if the body doesn't parse, so that the block around it can
+/// be kept as a plain expression. Bodies that start with a tag are clearly
+/// meant to be markup, though, so their errors are passed on instead.
+fn parse_body(body: &Group) -> Result<Option<Vec<SnaxItem>>, ParseError> {
+    match parse_siblings(body.stream(), false) {
+        Ok(items) => Ok(Some(items)),
+        Err(error) => match body.stream().into_iter().next() {
+            Some(TokenTree::Punct(ref punct)) if punct.as_char() == '<' => Err(error),
+            _ => Ok(None),
+        },
+    }
+}
@@ -0,0 +1,278 @@
+//! Rebuilds a syntax tree by value, in the style of `syn::fold`.
+//!
+//! Each method of [`Fold`] takes one type of node and by default passes it to
+//! the free function of the same name, which folds the node's children and
+//! puts it back together. Unlike `syn`, [`Fold::fold_item`] returns a list
+//! of items, so one item can be replaced by any number of items in its
+//! parent's children, including none.
+//!
+//! ```ignore
+//! struct RemoveComments;
+//!
+//! impl Fold for RemoveComments {
+//!     fn fold_item(&mut self, item: SnaxItem) -> Vec<SnaxItem> {
+//!         match item {
+//!             SnaxItem::Comment(_) => vec![],
+//!             item => vec![fold::fold_item(self, item)],
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`Fold`]: trait.Fold.html
+//! [`Fold::fold_item`]: trait.Fold.html#method.fold_item
+
+use proc_macro2::{Ident, TokenStream, TokenTree};
+
+use crate::{
+    SnaxAttribute, SnaxElse, SnaxItem, SnaxMatchArm, SnaxName, SnaxPath, SnaxSelfClosingTag,
+    SnaxTag, SnaxTagName,
+};
+
+/// Rebuilds a syntax tree, node by node. See the [module docs].
+///
+/// [module docs]: index.html
+pub trait Fold {
+    /// Folds an item into the items that take its place.
+    fn fold_item(&mut self, item: SnaxItem) -> Vec<SnaxItem> {
+        vec![fold_item(self, item)]
+    }
+
+    /// Folds a list of sibling items, like the children of a tag.
+    fn fold_items(&mut self, items: Vec<SnaxItem>) -> Vec<SnaxItem> {
+        fold_items(self, items)
+    }
+
+    fn fold_tag(&mut self, tag: SnaxTag) -> SnaxTag {
+        fold_tag(self, tag)
+    }
+
+    fn fold_self_closing_tag(&mut self, tag: SnaxSelfClosingTag) -> SnaxSelfClosingTag {
+        fold_self_closing_tag(self, tag)
+    }
+
+    fn fold_tag_name(&mut self, name: SnaxTagName) -> SnaxTagName {
+        fold_tag_name(self, name)
+    }
+
+    fn fold_name(&mut self, name: SnaxName) -> SnaxName {
+        fold_name(self, name)
+    }
+
+    fn fold_path(&mut self, path: SnaxPath) -> SnaxPath {
+        fold_path(self, path)
+    }
+
+    fn fold_attribute(&mut self, attribute: SnaxAttribute) -> SnaxAttribute {
+        fold_attribute(self, attribute)
+    }
+
+    /// Folds the literal or expression of a `SnaxAttribute::Simple`.
+    fn fold_attribute_value(&mut self, value: TokenTree) -> TokenTree {
+        fold_attribute_value(self, value)
+    }
+
+    /// Folds the expression of a `SnaxAttribute::Spread`.
+    fn fold_spread(&mut self, props: TokenStream) -> TokenStream {
+        fold_spread(self, props)
+    }
+
+    /// Folds the variable of a `SnaxAttribute::Shorthand`.
+    fn fold_shorthand(&mut self, name: Ident) -> Ident {
+        fold_shorthand(self, name)
+    }
+
+    /// Folds the expression or literal of a `SnaxItem::Content`.
+    fn fold_content(&mut self, content: TokenTree) -> TokenTree {
+        fold_content(self, content)
+    }
+
+    /// Folds the literal or expression inside a `SnaxItem::Comment`.
+    fn fold_comment(&mut self, content: TokenTree) -> TokenTree {
+        fold_comment(self, content)
+    }
+
+    /// Folds the pattern and initializer of a `SnaxItem::Let`.
+    fn fold_let(&mut self, pattern: TokenStream, init: TokenStream) -> (TokenStream, TokenStream) {
+        fold_let(self, pattern, init)
+    }
+
+    /// Folds the name of a `SnaxItem::Doctype`, like `html`.
+    fn fold_doctype(&mut self, name: Ident) -> Ident {
+        fold_doctype(self, name)
+    }
+
+    fn fold_else(&mut self, else_: SnaxElse) -> SnaxElse {
+        fold_else(self, else_)
+    }
+
+    fn fold_match_arm(&mut self, arm: SnaxMatchArm) -> SnaxMatchArm {
+        fold_match_arm(self, arm)
+    }
+}
+
+/// Folds the children of an item, keeping the item itself.
+pub fn fold_item<F: Fold + ?Sized>(folder: &mut F, item: SnaxItem) -> SnaxItem {
+    match item {
+        SnaxItem::Tag(tag) => SnaxItem::Tag(folder.fold_tag(tag)),
+        SnaxItem::SelfClosingTag(tag) => {
+            SnaxItem::SelfClosingTag(folder.fold_self_closing_tag(tag))
+        }
+        SnaxItem::Content(content) => SnaxItem::Content(folder.fold_content(content)),
+        SnaxItem::Fragment(children) => SnaxItem::Fragment(folder.fold_items(children)),
+        SnaxItem::If {
+            condition,
+            then,
+            else_,
+        } => SnaxItem::If {
+            condition,
+            then: folder.fold_items(then),
+            else_: else_.map(|else_| folder.fold_else(else_)),
+        },
+        SnaxItem::For {
+            pattern,
+            iterable,
+            body,
+        } => SnaxItem::For {
+            pattern,
+            iterable,
+            body: folder.fold_items(body),
+        },
+        SnaxItem::Match { scrutinee, arms } => SnaxItem::Match {
+            scrutinee,
+            arms: arms
+                .into_iter()
+                .map(|arm| folder.fold_match_arm(arm))
+                .collect(),
+        },
+        SnaxItem::Let { pattern, init } => {
+            let (pattern, init) = folder.fold_let(pattern, init);
+            SnaxItem::Let { pattern, init }
+        }
+        SnaxItem::Doctype { name } => SnaxItem::Doctype {
+            name: folder.fold_doctype(name),
+        },
+        SnaxItem::Comment(content) => SnaxItem::Comment(folder.fold_comment(content)),
+    }
+}
+
+pub fn fold_items<F: Fold + ?Sized>(folder: &mut F, items: Vec<SnaxItem>) -> Vec<SnaxItem> {
+    items
+        .into_iter()
+        .flat_map(|item| folder.fold_item(item))
+        .collect()
+}
+
+pub fn fold_tag<F: Fold + ?Sized>(folder: &mut F, tag: SnaxTag) -> SnaxTag {
+    SnaxTag {
+        name: folder.fold_tag_name(tag.name),
+        attributes: tag
+            .attributes
+            .into_iter()
+            .map(|attribute| folder.fold_attribute(attribute))
+            .collect(),
+        children: folder.fold_items(tag.children),
+    }
+}
+
+pub fn fold_self_closing_tag<F: Fold + ?Sized>(
+    folder: &mut F,
+    tag: SnaxSelfClosingTag,
+) -> SnaxSelfClosingTag {
+    SnaxSelfClosingTag {
+        name: folder.fold_tag_name(tag.name),
+        attributes: tag
+            .attributes
+            .into_iter()
+            .map(|attribute| folder.fold_attribute(attribute))
+            .collect(),
+    }
+}
+
+pub fn fold_tag_name<F: Fold + ?Sized>(folder: &mut F, name: SnaxTagName) -> SnaxTagName {
+    match name {
+        SnaxTagName::Element(name) => SnaxTagName::Element(folder.fold_name(name)),
+        SnaxTagName::Path(path) => SnaxTagName::Path(folder.fold_path(path)),
+    }
+}
+
+pub fn fold_name<F: Fold + ?Sized>(_folder: &mut F, name: SnaxName) -> SnaxName {
+    name
+}
+
+pub fn fold_path<F: Fold + ?Sized>(_folder: &mut F, path: SnaxPath) -> SnaxPath {
+    path
+}
+
+pub fn fold_attribute<F: Fold + ?Sized>(folder: &mut F, attribute: SnaxAttribute) -> SnaxAttribute {
+    match attribute {
+        SnaxAttribute::Simple { name, value } => SnaxAttribute::Simple {
+            name: folder.fold_name(name),
+            value: folder.fold_attribute_value(value),
+        },
+        SnaxAttribute::Boolean { name } => SnaxAttribute::Boolean {
+            name: folder.fold_name(name),
+        },
+        SnaxAttribute::Spread(props) => SnaxAttribute::Spread(folder.fold_spread(props)),
+        SnaxAttribute::Shorthand { name } => SnaxAttribute::Shorthand {
+            name: folder.fold_shorthand(name),
+        },
+    }
+}
+
+pub fn fold_attribute_value<F: Fold + ?Sized>(_folder: &mut F, value: TokenTree) -> TokenTree {
+    value
+}
+
+pub fn fold_spread<F: Fold + ?Sized>(_folder: &mut F, props: TokenStream) -> TokenStream {
+    props
+}
+
+pub fn fold_shorthand<F: Fold + ?Sized>(_folder: &mut F, name: Ident) -> Ident {
+    name
+}
+
+pub fn fold_content<F: Fold + ?Sized>(_folder: &mut F, content: TokenTree) -> TokenTree {
+    content
+}
+
+pub fn fold_comment<F: Fold + ?Sized>(_folder: &mut F, content: TokenTree) -> TokenTree {
+    content
+}
+
+pub fn fold_let<F: Fold + ?Sized>(
+    _folder: &mut F,
+    pattern: TokenStream,
+    init: TokenStream,
+) -> (TokenStream, TokenStream) {
+    (pattern, init)
+}
+
+pub fn fold_doctype<F: Fold + ?Sized>(_folder: &mut F, name: Ident) -> Ident {
+    name
+}
+
+/// Folds the body of an `else`. If an `else if` is folded into anything but a
+/// single `if`, the items become a plain `else` block.
+pub fn fold_else<F: Fold + ?Sized>(folder: &mut F, else_: SnaxElse) -> SnaxElse {
+    match else_ {
+        SnaxElse::If(item) => {
+            let mut items = folder.fold_item(*item);
+
+            if items.len() == 1 && matches!(items[0], SnaxItem::If { .. }) {
+                SnaxElse::If(Box::new(items.remove(0)))
+            } else {
+                SnaxElse::Block(items)
+            }
+        }
+        SnaxElse::Block(items) => SnaxElse::Block(folder.fold_items(items)),
+    }
+}
+
+pub fn fold_match_arm<F: Fold + ?Sized>(folder: &mut F, arm: SnaxMatchArm) -> SnaxMatchArm {
+    SnaxMatchArm {
+        pattern: arm.pattern,
+        guard: arm.guard,
+        body: folder.fold_items(arm.body),
+    }
+}
@@ -1,7 +1,269 @@
+mod control;
+pub mod fold;
+pub mod render;
+#[cfg(feature = "syn")]
+mod syn_parse;
+mod to_tokens;
 mod tokenizer;
+pub mod visit;
+pub mod visit_mut;
+
+use std::error::Error;
+use std::fmt;
+
+use crate::control::parse_content;
+use crate::tokenizer::{
+    parse_html_token, HtmlFragmentToken, HtmlOpenToken, HtmlToken, TokenizeError,
+};
+use proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
+use quote::quote_spanned;
+
+/// The name of a tag or attribute.
+///
+/// HTML names can contain dashes, which Rust splits into several tokens, so
+/// each half of a name is kept as the identifiers between the dashes:
+///
+/// ```html
+/// <my-widget data-id="3" />
+///  ^^^^^^^^^ ^^^^^^^
+///  SnaxName { namespace: None, local: [Ident(my), Ident(widget)] }
+/// ```
+///
+/// SVG and XML names can also have a namespace prefix, which is kept apart
+/// from the local name so that it can be mapped to the right namespace:
+///
+/// ```html
+/// <use xlink:href="#icon" />
+///      ^^^^^^^^^^
+///      SnaxName { namespace: Some([Ident(xlink)]), local: [Ident(href)] }
+/// ```
+///
+/// Its `Display` implementation writes the name back out as it was written.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnaxName {
+    pub namespace: Option<Vec<Ident>>,
+    pub local: Vec<Ident>,
+}
+
+impl SnaxName {
+    /// Creates a name from its written form, like `data-id` or `xlink:href`,
+    /// giving each part `span`.
+    ///
+    /// Panics if any of the parts isn't a valid identifier, in the same way
+    /// that `Ident::new` does.
+    pub fn new(name: &str, span: Span) -> SnaxName {
+        let parts = |name: &str| name.split('-').map(|part| Ident::new(part, span)).collect();
+
+        match name.find(':') {
+            Some(colon) => SnaxName {
+                namespace: Some(parts(&name[..colon])),
+                local: parts(&name[colon + 1..]),
+            },
+            None => SnaxName {
+                namespace: None,
+                local: parts(name),
+            },
+        }
+    }
+
+    /// The span covering the whole name.
+    ///
+    /// Spans can only be joined with `procmacro2_semver_exempt` enabled, so
+    /// otherwise this is the span of the first part.
+    pub fn span(&self) -> Span {
+        let first = self.namespace.as_ref().unwrap_or(&self.local)[0].span();
+
+        #[cfg(procmacro2_semver_exempt)]
+        {
+            let last = self.local[self.local.len() - 1].span();
+            if let Some(joined) = first.join(last) {
+                return joined;
+            }
+        }
+
+        first
+    }
+}
+
+impl From<Ident> for SnaxName {
+    fn from(ident: Ident) -> SnaxName {
+        SnaxName {
+            namespace: None,
+            local: vec![ident],
+        }
+    }
+}
+
+impl<T: ?Sized + AsRef<str>> PartialEq<T> for SnaxName {
+    fn eq(&self, other: &T) -> bool {
+        fn parts_eq(parts: &[Ident], other: &str) -> bool {
+            other.split('-').eq(parts.iter().map(Ident::to_string))
+        }
+
+        let other = other.as_ref();
+        match (&self.namespace, other.find(':')) {
+            (Some(namespace), Some(colon)) => {
+                parts_eq(namespace, &other[..colon]) && parts_eq(&self.local, &other[colon + 1..])
+            }
+            (None, None) => parts_eq(&self.local, other),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for SnaxName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn write_parts(f: &mut fmt::Formatter, parts: &[Ident]) -> fmt::Result {
+            for (i, part) in parts.iter().enumerate() {
+                if i > 0 {
+                    f.write_str("-")?;
+                }
+                write!(f, "{}", part)?;
+            }
+
+            Ok(())
+        }
+
+        if let Some(namespace) = &self.namespace {
+            write_parts(f, namespace)?;
+            f.write_str(":")?;
+        }
+
+        write_parts(f, &self.local)
+    }
+}
+
+/// The name of a [`SnaxTag`] or [`SnaxSelfClosingTag`].
+///
+/// A name that starts with an uppercase letter or contains `::` refers to a
+/// component, which is a Rust type, and everything else is an HTML element:
+///
+/// ```html
+/// <my-widget />       SnaxTagName::Element(SnaxName(my-widget))
+/// <Button />          SnaxTagName::Path(SnaxPath(Button))
+/// <ui::List<Row> />   SnaxTagName::Path(SnaxPath(ui::List<Row>))
+/// ```
+///
+/// [`SnaxTag`]: struct.SnaxTag.html
+/// [`SnaxSelfClosingTag`]: struct.SnaxSelfClosingTag.html
+#[derive(Debug, Clone, PartialEq)]
+pub enum SnaxTagName {
+    Element(SnaxName),
+    Path(SnaxPath),
+}
+
+impl SnaxTagName {
+    /// The span covering the whole name, see [`SnaxName::span`].
+    ///
+    /// [`SnaxName::span`]: struct.SnaxName.html#method.span
+    pub fn span(&self) -> Span {
+        match self {
+            SnaxTagName::Element(name) => name.span(),
+            SnaxTagName::Path(path) => path.span(),
+        }
+    }
+}
+
+impl From<SnaxName> for SnaxTagName {
+    fn from(name: SnaxName) -> SnaxTagName {
+        SnaxTagName::Element(name)
+    }
+}
+
+impl From<SnaxPath> for SnaxTagName {
+    fn from(path: SnaxPath) -> SnaxTagName {
+        SnaxTagName::Path(path)
+    }
+}
+
+impl fmt::Display for SnaxTagName {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SnaxTagName::Element(name) => name.fmt(f),
+            SnaxTagName::Path(path) => path.fmt(f),
+        }
+    }
+}
+
+/// The path of a component tag, like `ui::Button` or `List<Row>`.
+///
+/// Closing tags have to repeat the path exactly, generics included.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SnaxPath {
+    pub segments: Vec<SnaxPathSegment>,
+}
+
+impl SnaxPath {
+    /// The span covering the whole path.
+    ///
+    /// Like [`SnaxName::span`], this is the span of the first segment unless
+    /// `procmacro2_semver_exempt` is enabled.
+    ///
+    /// [`SnaxName::span`]: struct.SnaxName.html#method.span
+    pub fn span(&self) -> Span {
+        let first = self.segments[0].ident.span();
+
+        #[cfg(procmacro2_semver_exempt)]
+        {
+            let last = self.segments[self.segments.len() - 1].ident.span();
+            if let Some(joined) = first.join(last) {
+                return joined;
+            }
+        }
+
+        first
+    }
+}
+
+impl From<Ident> for SnaxPath {
+    fn from(ident: Ident) -> SnaxPath {
+        SnaxPath {
+            segments: vec![SnaxPathSegment {
+                ident,
+                arguments: None,
+            }],
+        }
+    }
+}
+
+impl fmt::Display for SnaxPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str("::")?;
+            }
+            write!(f, "{}", segment.ident)?;
+
+            if let Some(arguments) = &segment.arguments {
+                write!(f, "<{}>", arguments)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// One segment of a [`SnaxPath`], with the generic arguments written after
+/// it, if any.
+///
+/// [`SnaxPath`]: struct.SnaxPath.html
+#[derive(Debug, Clone)]
+pub struct SnaxPathSegment {
+    pub ident: Ident,
+
+    /// The tokens between `<` and `>`, without the angle brackets.
+    pub arguments: Option<TokenStream>,
+}
+
+impl PartialEq for SnaxPathSegment {
+    fn eq(&self, other: &SnaxPathSegment) -> bool {
+        let arguments = self.arguments.as_ref().map(TokenStream::to_string);
+        let other_arguments = other.arguments.as_ref().map(TokenStream::to_string);
+
+        self.ident == other.ident && arguments == other_arguments
+    }
+}
 
-use crate::tokenizer::{parse_html_token, HtmlOpenToken, HtmlToken, TokenizeError};
-use proc_macro2::{Ident, TokenStream, TokenTree};
 /// An attribute that's present on either a [`SnaxTag`] or a
 /// [`SnaxSelfClosingTag`].
 ///
@@ -13,11 +275,42 @@ pub enum SnaxAttribute {
     /// <div foo="bar" />
     ///      ^^^^^^^^^
     ///      SnaxAttribute::Simple {
-    ///          name: Ident(foo),
+    ///          name: SnaxName(foo),
     ///          value: TokenTree("bar"),
     ///      }
     /// ```
-    Simple { name: Ident, value: TokenTree },
+    Simple { name: SnaxName, value: TokenTree },
+
+    /// ```html
+    /// <input disabled />
+    ///        ^^^^^^^^
+    ///        SnaxAttribute::Boolean {
+    ///            name: SnaxName(disabled),
+    ///        }
+    /// ```
+    Boolean { name: SnaxName },
+
+    /// ```html
+    /// <div {..props} />
+    ///      ^^^^^^^^^
+    ///      SnaxAttribute::Spread(TokenStream(props))
+    /// ```
+    ///
+    /// Attributes keep the order they were written in, so generators can let
+    /// attributes after a spread override the ones that came from it.
+    Spread(TokenStream),
+
+    /// ```html
+    /// <img {src} />
+    ///      ^^^^^
+    ///      SnaxAttribute::Shorthand {
+    ///          name: Ident(src),
+    ///      }
+    /// ```
+    ///
+    /// This means the same as `src={src}`, but is kept apart so that
+    /// formatters can write it back out the way it was written.
+    Shorthand { name: Ident },
 }
 
 impl PartialEq for SnaxAttribute {
@@ -32,6 +325,10 @@ impl PartialEq for SnaxAttribute {
                     value: other_value,
                 },
             ) => name == other_name && value.to_string() == other_value.to_string(),
+            (Boolean { name }, Boolean { name: other_name }) => name == other_name,
+            (Spread(this), Spread(other)) => this.to_string() == other.to_string(),
+            (Shorthand { name }, Shorthand { name: other_name }) => name == other_name,
+            _ => false,
         }
     }
 }
@@ -50,6 +347,90 @@ pub enum SnaxItem {
 
     /// A block of content, which can contain any Rust expression.
     Content(TokenTree),
+
+    /// Several items without a wrapping tag:
+    ///
+    /// ```html
+    /// <>"Hello, " <b>"world"</b></>
+    /// ```
+    Fragment(Vec<SnaxItem>),
+
+    /// A content block holding an `if` expression whose branches are markup:
+    ///
+    /// ```html
+    /// {if user.is_admin { <a href="/admin">"Admin"</a> } else { "Welcome" }}
+    /// ```
+    ///
+    /// A block only becomes an `If` if every branch parses as markup.
+    /// Otherwise, like `{if a { b } else { c }}`, it stays a plain
+    /// `Content` expression.
+    If {
+        condition: TokenStream,
+        then: Vec<SnaxItem>,
+        else_: Option<SnaxElse>,
+    },
+
+    /// A content block holding a `for` loop whose body is markup:
+    ///
+    /// ```html
+    /// <ul>{for item in items { <li>{item}</li> }}</ul>
+    /// ```
+    ///
+    /// The body is rendered once per iteration, with `pattern` bound.
+    For {
+        pattern: TokenStream,
+        iterable: TokenStream,
+        body: Vec<SnaxItem>,
+    },
+
+    /// A content block holding a `match` expression whose arms are markup:
+    ///
+    /// ```html
+    /// {match state {
+    ///     State::Loading => <spinner />,
+    ///     State::Failed(error) if error.is_fatal() => <p>"Oops"</p>,
+    ///     State::Ready(data) => { <p>{data}</p> }
+    /// }}
+    /// ```
+    Match {
+        scrutinee: TokenStream,
+        arms: Vec<SnaxMatchArm>,
+    },
+
+    /// A content block holding a single `let` statement:
+    ///
+    /// ```html
+    /// <div>
+    ///     {let total = items.iter().sum::<u32>();}
+    ///     <p>{total}</p>
+    ///     <p>{total * 2}</p>
+    /// </div>
+    /// ```
+    ///
+    /// The binding is in scope for the siblings that come after it in the same
+    /// list of children, including their descendants, but not outside of the
+    /// parent it appears in. It renders nothing by itself.
+    Let {
+        pattern: TokenStream,
+        init: TokenStream,
+    },
+
+    /// A document type declaration:
+    ///
+    /// ```html
+    /// <!DOCTYPE html>
+    /// ```
+    ///
+    /// Like in HTML, it's only allowed as the first item of a document, with
+    /// nothing but comments before it.
+    Doctype { name: Ident },
+
+    /// A comment, holding either a string literal or a block:
+    ///
+    /// ```html
+    /// <!-- "Generated by rust_jsx" -->
+    /// ```
+    Comment(TokenTree),
 }
 
 impl PartialEq for SnaxItem {
@@ -60,11 +441,104 @@ impl PartialEq for SnaxItem {
             (Tag(this), Tag(other)) => this == other,
             (SelfClosingTag(this), SelfClosingTag(other)) => this == other,
             (Content(this), Content(other)) => this.to_string() == other.to_string(),
+            (Fragment(this), Fragment(other)) => this == other,
+            (
+                If {
+                    condition,
+                    then,
+                    else_,
+                },
+                If {
+                    condition: other_condition,
+                    then: other_then,
+                    else_: other_else,
+                },
+            ) => {
+                condition.to_string() == other_condition.to_string()
+                    && then == other_then
+                    && else_ == other_else
+            }
+            (
+                For {
+                    pattern,
+                    iterable,
+                    body,
+                },
+                For {
+                    pattern: other_pattern,
+                    iterable: other_iterable,
+                    body: other_body,
+                },
+            ) => {
+                pattern.to_string() == other_pattern.to_string()
+                    && iterable.to_string() == other_iterable.to_string()
+                    && body == other_body
+            }
+            (
+                Match { scrutinee, arms },
+                Match {
+                    scrutinee: other_scrutinee,
+                    arms: other_arms,
+                },
+            ) => scrutinee.to_string() == other_scrutinee.to_string() && arms == other_arms,
+            (
+                Let { pattern, init },
+                Let {
+                    pattern: other_pattern,
+                    init: other_init,
+                },
+            ) => {
+                pattern.to_string() == other_pattern.to_string()
+                    && init.to_string() == other_init.to_string()
+            }
+            (Doctype { name }, Doctype { name: other_name }) => name == other_name,
+            (Comment(this), Comment(other)) => this.to_string() == other.to_string(),
             _ => false,
         }
     }
 }
 
+/// The `else` branch of a [`SnaxItem::If`].
+///
+/// [`SnaxItem::If`]: enum.SnaxItem.html#variant.If
+#[derive(Debug, PartialEq)]
+pub enum SnaxElse {
+    /// `else if ... { ... }`, which always holds another `SnaxItem::If`.
+    If(Box<SnaxItem>),
+
+    /// `else { ... }`
+    Block(Vec<SnaxItem>),
+}
+
+/// One arm of a [`SnaxItem::Match`]:
+///
+/// ```html
+/// Some(user) if user.is_admin => <a href="/admin">"Admin"</a>,
+/// ^^^^^^^^^^    ^^^^^^^^^^^^^    ^^^^^^^^^^^^^^^^^^^^^^^^^^^^
+/// pattern       guard            body
+/// ```
+///
+/// The body is either a braced block or markup up to the next comma.
+///
+/// [`SnaxItem::Match`]: enum.SnaxItem.html#variant.Match
+#[derive(Debug)]
+pub struct SnaxMatchArm {
+    pub pattern: TokenStream,
+    pub guard: Option<TokenStream>,
+    pub body: Vec<SnaxItem>,
+}
+
+impl PartialEq for SnaxMatchArm {
+    fn eq(&self, other: &Self) -> bool {
+        let guard = self.guard.as_ref().map(TokenStream::to_string);
+        let other_guard = other.guard.as_ref().map(TokenStream::to_string);
+
+        self.pattern.to_string() == other.pattern.to_string()
+            && guard == other_guard
+            && self.body == other.body
+    }
+}
+
 /// A standard tag, which can have attributes and children.
 ///
 /// ```html
@@ -72,7 +546,7 @@ impl PartialEq for SnaxItem {
 /// ```
 #[derive(Debug, PartialEq)]
 pub struct SnaxTag {
-    pub name: Ident,
+    pub name: SnaxTagName,
     pub attributes: Vec<SnaxAttribute>,
     pub children: Vec<SnaxItem>,
 }
@@ -85,21 +559,123 @@ pub struct SnaxTag {
 ///
 #[derive(Debug, PartialEq)]
 pub struct SnaxSelfClosingTag {
-    pub name: Ident,
+    pub name: SnaxTagName,
     pub attributes: Vec<SnaxAttribute>,
 }
 
+/// Everything that can go wrong while parsing. Every variant can point at the
+/// offending source through [`ParseError::span`].
+///
+/// [`ParseError::span`]: enum.ParseError.html#method.span
 #[derive(Debug)]
 pub enum ParseError {
-    UnexpectedEnd,
+    /// The input ended before the markup was complete. `span` is the last
+    /// token that was read and `unclosed` is the innermost tag that was still
+    /// open, if any.
+    UnexpectedEnd {
+        span: Span,
+        unclosed: Option<Span>,
+    },
     UnexpectedItem(HtmlToken),
     UnexpectedToken(TokenTree),
+
+    /// A closing tag didn't match the innermost open tag, like
+    /// `<div></span>`.
+    MismatchedCloseTag {
+        open: SnaxTagName,
+        close: SnaxTagName,
+    },
+}
+
+impl ParseError {
+    /// The span the error should be reported at.
+    pub fn span(&self) -> Span {
+        match self {
+            ParseError::UnexpectedEnd { span, .. } => *span,
+            ParseError::UnexpectedItem(item) => item.span(),
+            ParseError::UnexpectedToken(token) => token.span(),
+            ParseError::MismatchedCloseTag { close, .. } => close.span(),
+        }
+    }
+
+    /// Turns the error into `compile_error!` invocations, so that a procedural
+    /// macro can return it in place of its expansion.
+    ///
+    /// Errors that involve two places in the source, like an unclosed tag,
+    /// produce a second error pointing at the other place.
+    pub fn to_compile_error(&self) -> TokenStream {
+        let mut errors = compile_error(self.span(), &self.to_string());
+
+        match self {
+            ParseError::UnexpectedEnd {
+                unclosed: Some(unclosed),
+                ..
+            } => errors.extend(compile_error(*unclosed, "this tag is never closed")),
+            ParseError::MismatchedCloseTag { open, .. } => errors.extend(compile_error(
+                open.span(),
+                &format!("`<{}>` is opened here", open),
+            )),
+            _ => {}
+        }
+
+        errors
+    }
 }
 
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    let mut message = Literal::string(message);
+    message.set_span(span);
+
+    quote_spanned!(span=> compile_error!(#message);)
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEnd { unclosed: None, .. } => {
+                write!(f, "unexpected end of input")
+            }
+            ParseError::UnexpectedEnd {
+                unclosed: Some(_), ..
+            } => write!(f, "unexpected end of input, expected a closing tag"),
+            ParseError::UnexpectedItem(HtmlToken::OpenTag(tag)) => {
+                write!(f, "unexpected tag `<{}>`", tag.name)
+            }
+            ParseError::UnexpectedItem(HtmlToken::CloseTag(tag)) => {
+                write!(f, "unexpected closing tag `</{}>`", tag.name)
+            }
+            ParseError::UnexpectedItem(HtmlToken::SelfClosingTag(tag)) => {
+                write!(f, "unexpected tag `<{} />`", tag.name)
+            }
+            ParseError::UnexpectedItem(HtmlToken::Textish(textish)) => {
+                write!(f, "unexpected content `{}`", textish.content)
+            }
+            ParseError::UnexpectedItem(HtmlToken::OpenFragment(_)) => write!(f, "unexpected `<>`"),
+            ParseError::UnexpectedItem(HtmlToken::CloseFragment(_)) => {
+                write!(f, "unexpected `</>`")
+            }
+            ParseError::UnexpectedItem(HtmlToken::Doctype(_)) => write!(
+                f,
+                "`<!DOCTYPE>` is only allowed at the start of the document"
+            ),
+            ParseError::UnexpectedItem(HtmlToken::Comment(_)) => write!(f, "unexpected comment"),
+            ParseError::UnexpectedToken(token) => write!(f, "unexpected token `{}`", token),
+            ParseError::MismatchedCloseTag { open, close } => {
+                write!(f, "expected `</{}>`, found `</{}>`", open, close)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
+
 impl From<TokenizeError> for ParseError {
     fn from(error: TokenizeError) -> ParseError {
         match error {
-            TokenizeError::UnexpectedEnd => ParseError::UnexpectedEnd,
+            TokenizeError::UnexpectedEnd(span) => ParseError::UnexpectedEnd {
+                span,
+                unclosed: None,
+            },
             TokenizeError::UnexpectedToken(token) => ParseError::UnexpectedToken(token),
         }
     }
@@ -117,28 +693,253 @@ macro_rules! expect_end {
 #[derive(Debug)]
 enum OpenToken {
     Tag(HtmlOpenToken),
+    Fragment(HtmlFragmentToken),
+}
+
+impl OpenToken {
+    fn span(&self) -> Span {
+        match self {
+            OpenToken::Tag(tag) => tag.name.span(),
+            OpenToken::Fragment(fragment) => fragment.span,
+        }
+    }
 }
 
 /// Attempts to parse a `proc_macro2::TokenStream` into a `SnaxItem`.
 pub fn parse(input_stream: TokenStream) -> Result<SnaxItem, ParseError> {
     let mut input = input_stream.into_iter();
-    let mut tag_stack: Vec<(OpenToken, Vec<SnaxItem>)> = Vec::new();
+    let item = parse_item(&mut input, true)?;
+
+    expect_end!(input);
+    Ok(item)
+}
+
+/// Attempts to parse a `proc_macro2::TokenStream` into any number of sibling
+/// `SnaxItem`s, like `<li /> <li />`.
+pub fn parse_many(input_stream: TokenStream) -> Result<Vec<SnaxItem>, ParseError> {
+    parse_siblings(input_stream, true)
+}
+
+/// Parses any number of sibling items. Unless they make up a whole
+/// `document`, they can't contain a doctype.
+fn parse_siblings(input_stream: TokenStream, document: bool) -> Result<Vec<SnaxItem>, ParseError> {
+    let mut input = input_stream.into_iter().peekable();
+    let mut parser = Parser::with_doctype(document);
+    let mut items: Vec<SnaxItem> = Vec::new();
+
+    while input.peek().is_some() {
+        items.push(parser.read_item(&mut input)?);
+    }
+
+    Ok(items)
+}
+
+/// Parses a single root item, leaving anything after it in `input`. The root
+/// item can only be a doctype if `doctype_allowed` is set.
+fn parse_item(
+    input: &mut impl Iterator<Item = TokenTree>,
+    doctype_allowed: bool,
+) -> Result<SnaxItem, ParseError> {
+    Parser::with_doctype(doctype_allowed).read_item(input)
+}
+
+/// What a [`Parser`] made of the tokens it has been fed so far.
+///
+/// [`Parser`]: struct.Parser.html
+#[derive(Debug, PartialEq)]
+pub enum ParseStatus {
+    /// The current root item isn't complete yet.
+    NeedMore,
+
+    /// The last token completed a root item.
+    Item(SnaxItem),
+}
+
+/// A parser that is fed one `TokenTree` at a time, for input that arrives in
+/// pieces or isn't complete yet, like a template that is being edited.
+///
+/// Feeding it the tokens of `<ul><li>"one"</li></ul> <br />` returns
+/// [`ParseStatus::NeedMore`] until the `>` of `</ul>`, which returns the whole
+/// `ul` as [`ParseStatus::Item`], and the same happens for the `br`. In
+/// between, [`open_tags`] tells which tags are open at that point.
+///
+/// [`ParseStatus::NeedMore`]: enum.ParseStatus.html#variant.NeedMore
+/// [`ParseStatus::Item`]: enum.ParseStatus.html#variant.Item
+/// [`open_tags`]: #method.open_tags
+#[derive(Debug)]
+pub struct Parser {
+    /// The tokens of the HTML token that's being read, which the tokenizer
+    /// goes over again whenever a token that could end it is fed.
+    pending: Vec<TokenTree>,
+    tag_stack: Vec<(OpenToken, Vec<SnaxItem>)>,
+
+    /// Whether the next root item can be a doctype, which is only the case
+    /// at the start of a document, after comments.
+    doctype_allowed: bool,
+    last_span: Span,
+}
+
+impl Parser {
+    /// Creates a parser for a document, which can start with a doctype.
+    pub fn new() -> Parser {
+        Parser::with_doctype(true)
+    }
+
+    fn with_doctype(doctype_allowed: bool) -> Parser {
+        Parser {
+            pending: Vec::new(),
+            tag_stack: Vec::new(),
+            doctype_allowed,
+            last_span: Span::call_site(),
+        }
+    }
+
+    /// Feeds the parser the next token of the input. Once the token completes
+    /// a root item, the item is returned and the parser moves on to the next
+    /// one.
+    ///
+    /// An unexpected token inside a tag may only be reported once the tag's
+    /// `>` is fed, or by `finish`. After an error, the parser shouldn't be fed
+    /// any more tokens.
+    pub fn feed(&mut self, token: TokenTree) -> Result<ParseStatus, ParseError> {
+        self.last_span = token.span();
+
+        // Every HTML token but text ends with a `>`, so going over the pending
+        // tokens before that can't finish one. Skipping it keeps long tags from
+        // being read over and over, while the first two tokens, which decide
+        // what kind of token it is, are still checked right away.
+        let is_close = matches!(&token, TokenTree::Punct(punct) if punct.as_char() == '>');
+
+        self.pending.push(token);
+        if !is_close && self.pending.len() > 2 {
+            return Ok(ParseStatus::NeedMore);
+        }
+
+        let token = match parse_html_token(self.pending.iter().cloned()) {
+            Ok(token) => token,
+            Err(TokenizeError::UnexpectedEnd(_)) => return Ok(ParseStatus::NeedMore),
+            Err(error) => return Err(error.into()),
+        };
+
+        // The tokenizer never reads past the end of a token, so the token that
+        // was just fed is the last one of it.
+        self.pending.clear();
+
+        match self.push_token(token)? {
+            Some(item) => Ok(ParseStatus::Item(item)),
+            None => Ok(ParseStatus::NeedMore),
+        }
+    }
+
+    /// Reads the next root item straight from `input`, leaving anything after
+    /// it. Unlike `feed`, this reads every token only once, since the input
+    /// is all there.
+    fn read_item(
+        &mut self,
+        input: &mut impl Iterator<Item = TokenTree>,
+    ) -> Result<SnaxItem, ParseError> {
+        loop {
+            let last_span = &mut self.last_span;
+            let tokens = (&mut *input).inspect(|token| *last_span = token.span());
+
+            let token = match parse_html_token(tokens) {
+                Ok(token) => token,
+                // The tokenizer starts over for every HTML token, so it only
+                // knows the spans of the tokens in the current one.
+                Err(TokenizeError::UnexpectedEnd(_)) => return Err(self.unexpected_end()),
+                Err(error) => return Err(error.into()),
+            };
+
+            if let Some(item) = self.push_token(token)? {
+                return Ok(item);
+            }
+        }
+    }
+
+    /// The names of the tags that are currently open, outermost first.
+    /// Fragments don't have a name, so they're skipped.
+    pub fn open_tags(&self) -> impl Iterator<Item = &SnaxTagName> {
+        self.tag_stack
+            .iter()
+            .filter_map(|(open_token, _)| match open_token {
+                OpenToken::Tag(tag) => Some(&tag.name),
+                OpenToken::Fragment(_) => None,
+            })
+    }
+
+    /// Whether the parser is between two root items, where the input can end.
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty() && self.tag_stack.is_empty()
+    }
+
+    /// Tells the parser that the input has ended, which is an error in the
+    /// middle of a root item.
+    pub fn finish(self) -> Result<(), ParseError> {
+        if self.is_complete() {
+            return Ok(());
+        }
+
+        // `feed` only reads the pending tokens once they could make up a whole
+        // HTML token, so they can still hold an unexpected token.
+        match parse_html_token(self.pending.iter().cloned()) {
+            Err(TokenizeError::UnexpectedEnd(_)) | Ok(_) => Err(self.unexpected_end()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    fn unexpected_end(&self) -> ParseError {
+        ParseError::UnexpectedEnd {
+            span: self.last_span,
+            unclosed: self
+                .tag_stack
+                .last()
+                .map(|(open_token, _)| open_token.span()),
+        }
+    }
+
+    /// Adds a complete item to the innermost open tag, or returns it if it's a
+    /// root item.
+    fn push_item(&mut self, item: SnaxItem) -> Option<SnaxItem> {
+        match self.tag_stack.last_mut() {
+            Some((_, parent_children)) => {
+                parent_children.push(item);
+                None
+            }
+            None => {
+                if !matches!(item, SnaxItem::Comment(_)) {
+                    self.doctype_allowed = false;
+                }
+                Some(item)
+            }
+        }
+    }
 
-    loop {
-        match parse_html_token(&mut input)? {
+    /// Handles an HTML token, returning the root item it completes, if any.
+    fn push_token(&mut self, token: HtmlToken) -> Result<Option<SnaxItem>, ParseError> {
+        match token {
             HtmlToken::OpenTag(opening_tag) => {
-                tag_stack.push((OpenToken::Tag(opening_tag), Vec::new()));
+                self.tag_stack
+                    .push((OpenToken::Tag(opening_tag), Vec::new()));
+                Ok(None)
             }
             HtmlToken::CloseTag(closing_tag) => {
-                let (open_token, children) = tag_stack.pop().ok_or_else(|| {
+                let (open_token, children) = self.tag_stack.pop().ok_or_else(|| {
                     ParseError::UnexpectedItem(HtmlToken::CloseTag(closing_tag.clone()))
                 })?;
 
                 let opening_tag = match open_token {
                     OpenToken::Tag(tag) => tag,
+                    OpenToken::Fragment(_) => {
+                        return Err(ParseError::UnexpectedItem(HtmlToken::CloseTag(closing_tag)))
+                    }
                 };
 
-                assert_eq!(opening_tag.name, closing_tag.name);
+                if opening_tag.name != closing_tag.name {
+                    return Err(ParseError::MismatchedCloseTag {
+                        open: opening_tag.name,
+                        close: closing_tag.name,
+                    });
+                }
 
                 let tag = SnaxTag {
                     name: opening_tag.name,
@@ -146,42 +947,55 @@ pub fn parse(input_stream: TokenStream) -> Result<SnaxItem, ParseError> {
                     children,
                 };
 
-                match tag_stack.last_mut() {
-                    None => {
-                        expect_end!(input);
-                        return Ok(SnaxItem::Tag(tag));
-                    }
-                    Some((_, parent_children)) => {
-                        parent_children.push(SnaxItem::Tag(tag));
-                    }
+                Ok(self.push_item(SnaxItem::Tag(tag)))
+            }
+
+            HtmlToken::OpenFragment(opening_fragment) => {
+                self.tag_stack
+                    .push((OpenToken::Fragment(opening_fragment), Vec::new()));
+                Ok(None)
+            }
+            HtmlToken::CloseFragment(closing_fragment) => {
+                let (open_token, children) = self.tag_stack.pop().ok_or_else(|| {
+                    ParseError::UnexpectedItem(HtmlToken::CloseFragment(closing_fragment.clone()))
+                })?;
+
+                if let OpenToken::Tag(_) = open_token {
+                    return Err(ParseError::UnexpectedItem(HtmlToken::CloseFragment(
+                        closing_fragment,
+                    )));
                 }
+
+                Ok(self.push_item(SnaxItem::Fragment(children)))
             }
 
+            HtmlToken::Doctype(doctype) => {
+                if !self.doctype_allowed || !self.tag_stack.is_empty() {
+                    return Err(ParseError::UnexpectedItem(HtmlToken::Doctype(doctype)));
+                }
+
+                Ok(self.push_item(SnaxItem::Doctype { name: doctype.name }))
+            }
+            HtmlToken::Comment(comment) => Ok(self.push_item(SnaxItem::Comment(comment.content))),
+
             HtmlToken::SelfClosingTag(self_closing_tag) => {
                 let tag = SnaxSelfClosingTag {
                     name: self_closing_tag.name,
                     attributes: self_closing_tag.attributes,
                 };
 
-                match tag_stack.last_mut() {
-                    None => {
-                        expect_end!(input);
-                        return Ok(SnaxItem::SelfClosingTag(tag));
-                    }
-                    Some((_, parent_children)) => {
-                        parent_children.push(SnaxItem::SelfClosingTag(tag));
-                    }
-                }
+                Ok(self.push_item(SnaxItem::SelfClosingTag(tag)))
+            }
+            HtmlToken::Textish(textish) => {
+                let item = parse_content(textish.content)?;
+                Ok(self.push_item(item))
             }
-            HtmlToken::Textish(textish) => match tag_stack.last_mut() {
-                None => {
-                    expect_end!(input);
-                    return Ok(SnaxItem::Content(textish.content));
-                }
-                Some((_, parent_children)) => {
-                    parent_children.push(SnaxItem::Content(textish.content));
-                }
-            },
         }
     }
-}
\ No newline at end of file
+}
+
+impl Default for Parser {
+    fn default() -> Parser {
+        Parser::new()
+    }
+}
@@ -0,0 +1,669 @@
+//! The runtime side of rendering: a [`Render`] trait for values that can be
+//! written out as HTML, and the [`Output`] they're written to.
+//!
+//! Rendering goes straight into any `fmt::Write` or `io::Write`, without
+//! building an intermediate `String`:
+//!
+//! ```ignore
+//! let mut page = String::new();
+//! ("Tom & Jerry", 2).render_to_fmt(&mut page)?;
+//! assert_eq!(page, "Tom &amp; Jerry2");
+//! ```
+//!
+//! [`Render`]: trait.Render.html
+//! [`Output`]: struct.Output.html
+
+use std::borrow::Cow;
+use std::fmt::{self, Write};
+use std::io;
+use std::mem;
+use std::rc::Rc;
+use std::sync::Arc;
+
+/// Where a value is being rendered, which decides how its text is escaped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    /// Text between tags.
+    Text,
+
+    /// A double-quoted attribute value.
+    Attribute,
+
+    /// A double-quoted attribute value that holds a URL, like `href` or
+    /// `src`, see [`attribute_context`]. URLs with a scheme that can run code,
+    /// like `javascript:`, are replaced with `about:invalid`.
+    ///
+    /// The URL is only checked once it's complete, so it's collected into a
+    /// `String` first. Markup written in this context is checked and escaped
+    /// along with the text, so [`Raw`] has no effect on URLs.
+    ///
+    /// [`attribute_context`]: fn.attribute_context.html
+    /// [`Raw`]: struct.Raw.html
+    Url,
+
+    /// The raw text of a `<script>`, where a value's text is written as a
+    /// double-quoted JavaScript string literal, so that it can only ever be
+    /// data. Markup, like [`Raw`], is written as it is, outside of the
+    /// string.
+    ///
+    /// [`Raw`]: struct.Raw.html
+    Script,
+
+    /// The raw text of a `<style>`, where text is escaped for a CSS string.
+    Style,
+}
+
+/// Attributes whose values are URLs.
+const URL_ATTRIBUTES: &[&str] = &[
+    "action",
+    "background",
+    "cite",
+    "codebase",
+    "data",
+    "formaction",
+    "href",
+    "icon",
+    "longdesc",
+    "manifest",
+    "ping",
+    "poster",
+    "src",
+    "xlink:href",
+];
+
+/// URL schemes that can't run code. URLs without a scheme are relative, so
+/// they're safe as well.
+const SAFE_SCHEMES: &[&str] = &["http", "https", "mailto", "tel"];
+
+/// The context for the value of the attribute called `name`, which is
+/// [`Context::Url`] for attributes like `href` and [`Context::Attribute`]
+/// otherwise.
+///
+/// [`Context::Url`]: enum.Context.html#variant.Url
+/// [`Context::Attribute`]: enum.Context.html#variant.Attribute
+pub fn attribute_context(name: &str) -> Context {
+    if URL_ATTRIBUTES
+        .iter()
+        .any(|attribute| attribute.eq_ignore_ascii_case(name))
+    {
+        Context::Url
+    } else {
+        Context::Attribute
+    }
+}
+
+/// Whether `name` can be written as an attribute name as it is, which is
+/// the case unless it's empty or holds whitespace, control characters or one
+/// of `"'>/=`. Names that come from runtime data, like those of a spread
+/// attribute, have to be checked, since escaping them doesn't keep a name like
+/// `x onclick=alert(1)` from adding another attribute.
+pub fn is_attribute_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.chars().any(|c| {
+            c.is_whitespace() || c.is_control() || matches!(c, '"' | '\'' | '>' | '/' | '=')
+        })
+}
+
+/// Whether `url` is relative or has one of the `SAFE_SCHEMES`.
+fn is_safe_url(url: &str) -> bool {
+    // Browsers ignore leading whitespace and control characters, as well as
+    // tabs and newlines anywhere in the URL, so `java\tscript:` is still a
+    // `javascript:` URL.
+    let url = url.trim_start_matches(|c: char| c <= ' ');
+    let scheme: String = url
+        .chars()
+        .take_while(|c| !matches!(c, ':' | '/' | '?' | '#'))
+        .filter(|c| !matches!(c, '\t' | '\n' | '\r'))
+        .collect();
+
+    match url.find([':', '/', '?', '#']) {
+        Some(end) if url[end..].starts_with(':') => SAFE_SCHEMES
+            .iter()
+            .any(|safe| safe.eq_ignore_ascii_case(&scheme)),
+        _ => true,
+    }
+}
+
+/// How a character has to be written in `context`, if it has to be escaped.
+fn escape_char(context: Context, c: char) -> Option<&'static str> {
+    match context {
+        Context::Text | Context::Attribute | Context::Url => match c {
+            '&' => Some("&amp;"),
+            '<' => Some("&lt;"),
+            '>' => Some("&gt;"),
+            '"' => Some("&quot;"),
+            '\'' => Some("&#39;"),
+            _ => None,
+        },
+        Context::Script => match c {
+            '\\' => Some("\\\\"),
+            '"' => Some("\\x22"),
+            '\'' => Some("\\x27"),
+            '`' => Some("\\x60"),
+            '<' => Some("\\x3C"),
+            '>' => Some("\\x3E"),
+            '&' => Some("\\x26"),
+            '\n' => Some("\\n"),
+            '\r' => Some("\\r"),
+            '\u{2028}' => Some("\\u2028"),
+            '\u{2029}' => Some("\\u2029"),
+            _ => None,
+        },
+        Context::Style => match c {
+            '\\' => Some("\\5c "),
+            '"' => Some("\\22 "),
+            '\'' => Some("\\27 "),
+            '<' => Some("\\3c "),
+            '>' => Some("\\3e "),
+            '&' => Some("\\26 "),
+            '(' => Some("\\28 "),
+            ')' => Some("\\29 "),
+            ';' => Some("\\3b "),
+            '{' => Some("\\7b "),
+            '}' => Some("\\7d "),
+            '\n' => Some("\\a "),
+            '\r' => Some("\\d "),
+            _ => None,
+        },
+    }
+}
+
+fn write_escaped(writer: &mut dyn fmt::Write, context: Context, text: &str) -> fmt::Result {
+    let mut start = 0;
+
+    for (i, c) in text.char_indices() {
+        if let Some(escaped) = escape_char(context, c) {
+            writer.write_str(&text[start..i])?;
+            writer.write_str(escaped)?;
+            start = i + c.len_utf8();
+        }
+    }
+
+    writer.write_str(&text[start..])
+}
+
+/// Where rendered HTML goes.
+///
+/// Text written through its `fmt::Write` implementation, like with `write!`,
+/// is escaped for the current [`Context`], while [`write_markup`] writes
+/// markup as it is.
+///
+/// [`Context`]: enum.Context.html
+/// [`write_markup`]: #method.write_markup
+pub struct Output<'a> {
+    inner: Target<'a>,
+    context: Context,
+
+    /// The URL that's being written in `Context::Url`.
+    url: String,
+
+    /// What has been written of the value in `Context::Script`.
+    script: ScriptValue,
+}
+
+/// What an `Output` writes to. A `String` is kept as it is, so that space
+/// can be reserved in it.
+enum Target<'a> {
+    String(&'a mut String),
+    Fmt(&'a mut dyn fmt::Write),
+}
+
+impl<'a> fmt::Write for Target<'a> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        match self {
+            Target::String(string) => string.write_str(text),
+            Target::Fmt(writer) => writer.write_str(text),
+        }
+    }
+}
+
+/// What has been written of a value in `Context::Script`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScriptValue {
+    Empty,
+
+    /// Text, whose string literal hasn't been closed yet.
+    Text,
+    Markup,
+}
+
+impl<'a> Output<'a> {
+    /// Creates an output that starts out in `Context::Text`.
+    pub fn new(inner: &'a mut dyn fmt::Write) -> Output<'a> {
+        Output::with_target(Target::Fmt(inner))
+    }
+
+    /// Creates an output that writes to a `String`, which lets [`reserve`]
+    /// make room in it.
+    ///
+    /// [`reserve`]: #method.reserve
+    pub fn for_string(string: &'a mut String) -> Output<'a> {
+        Output::with_target(Target::String(string))
+    }
+
+    fn with_target(inner: Target<'a>) -> Output<'a> {
+        Output {
+            inner,
+            context: Context::Text,
+            url: String::new(),
+            script: ScriptValue::Empty,
+        }
+    }
+
+    /// Makes room for about `additional` more bytes, like before rendering a
+    /// value with its [`Render::size_hint`]. This only does something when
+    /// writing to a `String`.
+    ///
+    /// [`Render::size_hint`]: trait.Render.html#method.size_hint
+    pub fn reserve(&mut self, additional: usize) {
+        if let Target::String(string) = &mut self.inner {
+            string.reserve(additional);
+        }
+    }
+
+    /// How many more bytes fit in the `String` being written to before it has
+    /// to grow, or `None` when not writing to a `String`.
+    pub fn spare_capacity(&self) -> Option<usize> {
+        match &self.inner {
+            Target::String(string) => Some(string.capacity() - string.len()),
+            Target::Fmt(_) => None,
+        }
+    }
+
+    /// The context that text is currently escaped for.
+    pub fn context(&self) -> Context {
+        self.context
+    }
+
+    /// Runs `f` with the output switched to `context`, switching back
+    /// afterwards.
+    pub fn with_context<F>(&mut self, context: Context, f: F) -> fmt::Result
+    where
+        F: FnOnce(&mut Output) -> fmt::Result,
+    {
+        let entering_script = context == Context::Script && self.context != Context::Script;
+        let previous_script = if entering_script {
+            mem::replace(&mut self.script, ScriptValue::Empty)
+        } else {
+            self.script
+        };
+
+        let previous = mem::replace(&mut self.context, context);
+        let result = f(self);
+        self.context = previous;
+
+        if entering_script {
+            let script = mem::replace(&mut self.script, previous_script);
+            result?;
+
+            // An empty value is still written as a string, so that it can't
+            // leave a hole in the code around it.
+            return match script {
+                ScriptValue::Empty => self.inner.write_str("\"\""),
+                ScriptValue::Text => self.inner.write_str("\""),
+                ScriptValue::Markup => Ok(()),
+            };
+        }
+
+        if context == Context::Url && previous != Context::Url {
+            let url = mem::take(&mut self.url);
+            result?;
+
+            let url = if is_safe_url(&url) {
+                &url[..]
+            } else {
+                "about:invalid"
+            };
+            return write_escaped(&mut self.inner, Context::Url, url);
+        }
+
+        result
+    }
+
+    /// Writes text, escaping it for the current context.
+    pub fn write_text(&mut self, text: &str) -> fmt::Result {
+        match self.context {
+            Context::Url => {
+                self.url.push_str(text);
+                Ok(())
+            }
+            Context::Script => {
+                if self.script != ScriptValue::Text {
+                    self.inner.write_str("\"")?;
+                    self.script = ScriptValue::Text;
+                }
+                write_escaped(&mut self.inner, Context::Script, text)
+            }
+            context => write_escaped(&mut self.inner, context, text),
+        }
+    }
+
+    /// Writes markup that is already valid HTML, as it is.
+    pub fn write_markup(&mut self, markup: &str) -> fmt::Result {
+        match self.context {
+            Context::Url => {
+                self.url.push_str(markup);
+                Ok(())
+            }
+            Context::Script => {
+                if self.script == ScriptValue::Text {
+                    self.inner.write_str("\"")?;
+                }
+                self.script = ScriptValue::Markup;
+                self.inner.write_str(markup)
+            }
+            _ => self.inner.write_str(markup),
+        }
+    }
+}
+
+impl<'a> fmt::Write for Output<'a> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.write_text(text)
+    }
+}
+
+/// Markup that is already escaped, which is rendered as it is.
+///
+/// This turns escaping off, so it must never hold text that comes from
+/// users.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Raw<T>(pub T);
+
+impl<T: AsRef<str>> Render for Raw<T> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        out.write_markup(self.0.as_ref())
+    }
+
+    fn size_hint(&self) -> usize {
+        self.0.as_ref().len()
+    }
+}
+
+/// A value that can be rendered as HTML.
+///
+/// Strings, numbers and everything else that is written as text gets escaped.
+/// `Option`s render their value if there is one, and lists render each of
+/// their elements in turn. Iterators and closures can be rendered through
+/// [`iter`] and [`from_fn`].
+///
+/// [`iter`]: fn.iter.html
+/// [`from_fn`]: fn.from_fn.html
+pub trait Render {
+    fn render(&self, out: &mut Output) -> fmt::Result;
+
+    /// An estimate of how many bytes rendering writes, which is used to
+    /// allocate the output up front. It doesn't have to be exact, and is `0`
+    /// unless it's overridden.
+    fn size_hint(&self) -> usize {
+        0
+    }
+
+    /// Renders into any `fmt::Write`, like a `String` or a `fmt::Formatter`.
+    fn render_to_fmt(&self, writer: &mut dyn fmt::Write) -> fmt::Result {
+        self.render(&mut Output::new(writer))
+    }
+
+    /// Renders into any `io::Write`, like a file or a socket, writing the
+    /// markup as UTF-8.
+    fn render_to_io(&self, writer: &mut dyn io::Write) -> io::Result<()> {
+        let mut adapter = IoAdapter {
+            inner: writer,
+            error: None,
+        };
+
+        match self.render_to_fmt(&mut adapter) {
+            Ok(()) => Ok(()),
+            Err(fmt::Error) => Err(adapter
+                .error
+                .unwrap_or_else(|| io::Error::other("rendering failed"))),
+        }
+    }
+
+    fn render_to_string(&self) -> String {
+        let mut string = String::with_capacity(self.size_hint());
+        self.render(&mut Output::for_string(&mut string))
+            .expect("a Render implementation returned an error unexpectedly");
+        string
+    }
+}
+
+/// Lets a `fmt::Write` stand in for an `io::Write`, keeping the `io::Error`
+/// that `fmt::Error` can't carry.
+struct IoAdapter<'a> {
+    inner: &'a mut dyn io::Write,
+    error: Option<io::Error>,
+}
+
+impl<'a> fmt::Write for IoAdapter<'a> {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.inner.write_all(text.as_bytes()).map_err(|error| {
+            self.error = Some(error);
+            fmt::Error
+        })
+    }
+}
+
+impl Render for str {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        out.write_text(self)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Render for String {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        out.write_text(self)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<'a> Render for Cow<'a, str> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        out.write_text(self)
+    }
+
+    fn size_hint(&self) -> usize {
+        self.len()
+    }
+}
+
+impl Render for fmt::Arguments<'_> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        out.write_fmt(*self)
+    }
+}
+
+/// Implements `Render` for types that are written out through `Display`.
+macro_rules! render_display {
+    ($($ty: ty),*) => {
+        $(
+            impl Render for $ty {
+                fn render(&self, out: &mut Output) -> fmt::Result {
+                    write!(out, "{}", self)
+                }
+            }
+        )*
+    };
+}
+
+render_display!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, bool, char
+);
+
+impl<T: Render + ?Sized> Render for &T {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        (**self).render(out)
+    }
+
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
+}
+
+impl<T: Render + ?Sized> Render for &mut T {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        (**self).render(out)
+    }
+
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
+}
+
+impl<T: Render + ?Sized> Render for Box<T> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        (**self).render(out)
+    }
+
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
+}
+
+impl<T: Render + ?Sized> Render for Rc<T> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        (**self).render(out)
+    }
+
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
+}
+
+impl<T: Render + ?Sized> Render for Arc<T> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        (**self).render(out)
+    }
+
+    fn size_hint(&self) -> usize {
+        (**self).size_hint()
+    }
+}
+
+impl<T: Render> Render for Option<T> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        match self {
+            Some(value) => value.render(out),
+            None => Ok(()),
+        }
+    }
+
+    fn size_hint(&self) -> usize {
+        self.as_ref().map_or(0, Render::size_hint)
+    }
+}
+
+impl<T: Render> Render for [T] {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        for value in self {
+            value.render(out)?;
+        }
+
+        Ok(())
+    }
+
+    fn size_hint(&self) -> usize {
+        self.iter().map(Render::size_hint).sum()
+    }
+}
+
+impl<T: Render> Render for Vec<T> {
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        self[..].render(out)
+    }
+
+    fn size_hint(&self) -> usize {
+        self[..].size_hint()
+    }
+}
+
+/// Implements `Render` for tuples, rendering each element in turn.
+macro_rules! render_tuple {
+    ($($name: ident)*) => {
+        impl<$($name: Render),*> Render for ($($name,)*) {
+            #[allow(non_snake_case)]
+            fn render(&self, out: &mut Output) -> fmt::Result {
+                let ($($name,)*) = self;
+                $($name.render(out)?;)*
+                Ok(())
+            }
+
+            #[allow(non_snake_case)]
+            fn size_hint(&self) -> usize {
+                let ($($name,)*) = self;
+                0 $(+ $name.size_hint())*
+            }
+        }
+    };
+}
+
+render_tuple!(A);
+render_tuple!(A B);
+render_tuple!(A B C);
+render_tuple!(A B C D);
+render_tuple!(A B C D E);
+render_tuple!(A B C D E F);
+
+/// Renders every item of an iterator, see [`iter`].
+///
+/// [`iter`]: fn.iter.html
+#[derive(Debug, Clone)]
+pub struct Iter<I>(I);
+
+/// Renders every item of an iterator, like
+/// `iter(names.iter().map(|name| (name, ", ")))`.
+///
+/// Rendering can't consume the iterator, so it's cloned every time it's
+/// rendered.
+pub fn iter<I>(iter: I) -> Iter<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: Render,
+{
+    Iter(iter)
+}
+
+impl<I> Render for Iter<I>
+where
+    I: IntoIterator + Clone,
+    I::Item: Render,
+{
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        for value in self.0.clone() {
+            value.render(out)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Renders by calling a closure, see [`from_fn`].
+///
+/// [`from_fn`]: fn.from_fn.html
+#[derive(Clone)]
+pub struct FromFn<F>(F);
+
+/// Renders by calling `f` with the output, for rendering logic that doesn't
+/// deserve a type of its own.
+pub fn from_fn<F>(f: F) -> FromFn<F>
+where
+    F: Fn(&mut Output) -> fmt::Result,
+{
+    FromFn(f)
+}
+
+impl<F> Render for FromFn<F>
+where
+    F: Fn(&mut Output) -> fmt::Result,
+{
+    fn render(&self, out: &mut Output) -> fmt::Result {
+        (self.0)(out)
+    }
+}
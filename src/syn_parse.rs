@@ -0,0 +1,112 @@
+//! `syn::parse::Parse` implementations, so that markup can be parsed as one
+//! part of a larger macro input, like `html!(context, <div />)`.
+//!
+//! Items and tags are read by the same tokenizer as [`parse`], which never
+//! reads past the end of the markup, so whatever comes after it is left in the
+//! `ParseStream`.
+//!
+//! [`parse`]: ../fn.parse.html
+
+use proc_macro2::{Group, Ident, TokenTree};
+use syn::buffer::Cursor;
+use syn::ext::IdentExt;
+use syn::parse::{Parse, ParseStream};
+use syn::{token, Token};
+
+use crate::tokenizer::parse_block_attribute;
+use crate::{parse_item, ParseError, SnaxAttribute, SnaxItem, SnaxName, SnaxTag};
+
+/// Iterates over the token trees after a `syn` cursor, keeping track of where
+/// the tokens that have been read end.
+struct CursorTokens<'a> {
+    cursor: Cursor<'a>,
+}
+
+impl<'a> Iterator for CursorTokens<'a> {
+    type Item = TokenTree;
+
+    fn next(&mut self) -> Option<TokenTree> {
+        let (token, rest) = self.cursor.token_tree()?;
+        self.cursor = rest;
+        Some(token)
+    }
+}
+
+fn to_syn_error(error: ParseError) -> syn::Error {
+    syn::Error::new(error.span(), error)
+}
+
+impl Parse for SnaxItem {
+    fn parse(input: ParseStream) -> syn::Result<SnaxItem> {
+        input.step(|cursor| {
+            let mut tokens = CursorTokens { cursor: *cursor };
+            let item = parse_item(&mut tokens, true).map_err(to_syn_error)?;
+
+            Ok((item, tokens.cursor))
+        })
+    }
+}
+
+impl Parse for SnaxTag {
+    fn parse(input: ParseStream) -> syn::Result<SnaxTag> {
+        let span = input.cursor().span();
+
+        match input.parse()? {
+            SnaxItem::Tag(tag) => Ok(tag),
+            _ => Err(syn::Error::new(span, "expected a tag with a closing tag")),
+        }
+    }
+}
+
+impl Parse for SnaxAttribute {
+    fn parse(input: ParseStream) -> syn::Result<SnaxAttribute> {
+        if input.peek(token::Brace) {
+            let group: Group = input.parse()?;
+            return parse_block_attribute(group).map_err(|error| to_syn_error(error.into()));
+        }
+
+        let name = parse_name(input)?;
+        if !input.peek(Token![=]) {
+            return Ok(SnaxAttribute::Boolean { name });
+        }
+
+        input.parse::<Token![=]>()?;
+        match input.parse()? {
+            value @ TokenTree::Literal(_) | value @ TokenTree::Group(_) => {
+                Ok(SnaxAttribute::Simple { name, value })
+            }
+            unexpected => Err(to_syn_error(ParseError::UnexpectedToken(unexpected))),
+        }
+    }
+}
+
+/// Parses a name the same way the tokenizer does. Any identifier is allowed,
+/// keywords included, since names like `type` are common in HTML.
+fn parse_name(input: ParseStream) -> syn::Result<SnaxName> {
+    let parts = parse_name_parts(input)?;
+
+    if input.peek(Token![:]) && !input.peek(Token![::]) {
+        input.parse::<Token![:]>()?;
+
+        Ok(SnaxName {
+            namespace: Some(parts),
+            local: parse_name_parts(input)?,
+        })
+    } else {
+        Ok(SnaxName {
+            namespace: None,
+            local: parts,
+        })
+    }
+}
+
+fn parse_name_parts(input: ParseStream) -> syn::Result<Vec<Ident>> {
+    let mut parts = vec![Ident::parse_any(input)?];
+
+    while input.peek(Token![-]) {
+        input.parse::<Token![-]>()?;
+        parts.push(Ident::parse_any(input)?);
+    }
+
+    Ok(parts)
+}
@@ -0,0 +1,276 @@
+//! `quote::ToTokens` implementations that write the syntax tree back out as
+//! markup, so that `parse(item.into_token_stream())` gives back `item`.
+//!
+//! Identifiers, literals and expressions keep their original spans. The
+//! punctuation around them isn't part of the tree, so it gets the span of the
+//! closest name or expression instead.
+
+use proc_macro2::{Delimiter, Group, Ident, Punct, Spacing, Span, TokenStream};
+use quote::{ToTokens, TokenStreamExt};
+
+use crate::{
+    SnaxAttribute, SnaxElse, SnaxItem, SnaxMatchArm, SnaxName, SnaxPath, SnaxSelfClosingTag,
+    SnaxTag, SnaxTagName,
+};
+
+/// Appends punctuation like `</` or `::`, with every character but the last
+/// joined to the next one.
+fn append_punct(tokens: &mut TokenStream, punct: &str, span: Span) {
+    let last = punct.len() - 1;
+
+    for (i, c) in punct.chars().enumerate() {
+        let spacing = if i == last {
+            Spacing::Alone
+        } else {
+            Spacing::Joint
+        };
+
+        let mut punct = Punct::new(c, spacing);
+        punct.set_span(span);
+        tokens.append(punct);
+    }
+}
+
+fn append_braced(tokens: &mut TokenStream, inner: TokenStream, span: Span) {
+    let mut group = Group::new(Delimiter::Brace, inner);
+    group.set_span(span);
+    tokens.append(group);
+}
+
+fn append_keyword(tokens: &mut TokenStream, keyword: &str, span: Span) {
+    tokens.append(Ident::new(keyword, span));
+}
+
+/// The span of the first token of an expression, or the call site if it's
+/// empty.
+fn first_span(stream: &TokenStream) -> Span {
+    stream
+        .clone()
+        .into_iter()
+        .next()
+        .map_or_else(Span::call_site, |token| token.span())
+}
+
+impl ToTokens for SnaxName {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        fn append_parts(tokens: &mut TokenStream, parts: &[Ident]) {
+            for (i, part) in parts.iter().enumerate() {
+                if i > 0 {
+                    append_punct(tokens, "-", part.span());
+                }
+                part.to_tokens(tokens);
+            }
+        }
+
+        if let Some(namespace) = &self.namespace {
+            append_parts(tokens, namespace);
+            append_punct(tokens, ":", self.local[0].span());
+        }
+
+        append_parts(tokens, &self.local);
+    }
+}
+
+impl ToTokens for SnaxPath {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                append_punct(tokens, "::", segment.ident.span());
+            }
+            segment.ident.to_tokens(tokens);
+
+            if let Some(arguments) = &segment.arguments {
+                append_punct(tokens, "<", segment.ident.span());
+                arguments.to_tokens(tokens);
+                append_punct(tokens, ">", segment.ident.span());
+            }
+        }
+    }
+}
+
+impl ToTokens for SnaxTagName {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            SnaxTagName::Element(name) => name.to_tokens(tokens),
+            SnaxTagName::Path(path) => path.to_tokens(tokens),
+        }
+    }
+}
+
+impl ToTokens for SnaxAttribute {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            SnaxAttribute::Simple { name, value } => {
+                name.to_tokens(tokens);
+                append_punct(tokens, "=", value.span());
+                value.to_tokens(tokens);
+            }
+            SnaxAttribute::Boolean { name } => name.to_tokens(tokens),
+            SnaxAttribute::Spread(props) => {
+                let span = first_span(props);
+
+                let mut inner = TokenStream::new();
+                append_punct(&mut inner, "..", span);
+                props.to_tokens(&mut inner);
+
+                append_braced(tokens, inner, span);
+            }
+            SnaxAttribute::Shorthand { name } => {
+                append_braced(tokens, name.into_token_stream(), name.span());
+            }
+        }
+    }
+}
+
+impl ToTokens for SnaxTag {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let span = self.name.span();
+
+        append_punct(tokens, "<", span);
+        self.name.to_tokens(tokens);
+        tokens.append_all(&self.attributes);
+        append_punct(tokens, ">", span);
+
+        tokens.append_all(&self.children);
+
+        append_punct(tokens, "</", span);
+        self.name.to_tokens(tokens);
+        append_punct(tokens, ">", span);
+    }
+}
+
+impl ToTokens for SnaxSelfClosingTag {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let span = self.name.span();
+
+        append_punct(tokens, "<", span);
+        self.name.to_tokens(tokens);
+        tokens.append_all(&self.attributes);
+        append_punct(tokens, "/>", span);
+    }
+}
+
+impl ToTokens for SnaxItem {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        match self {
+            SnaxItem::Tag(tag) => tag.to_tokens(tokens),
+            SnaxItem::SelfClosingTag(tag) => tag.to_tokens(tokens),
+            SnaxItem::Content(content) => content.to_tokens(tokens),
+            SnaxItem::Fragment(children) => {
+                append_punct(tokens, "<>", Span::call_site());
+                tokens.append_all(children);
+                append_punct(tokens, "</>", Span::call_site());
+            }
+            SnaxItem::If { condition, .. } => {
+                let mut inner = TokenStream::new();
+                if_to_tokens(self, &mut inner);
+
+                append_braced(tokens, inner, first_span(condition));
+            }
+            SnaxItem::For {
+                pattern,
+                iterable,
+                body,
+            } => {
+                let span = first_span(pattern);
+
+                let mut inner = TokenStream::new();
+                append_keyword(&mut inner, "for", span);
+                pattern.to_tokens(&mut inner);
+                append_keyword(&mut inner, "in", first_span(iterable));
+                iterable.to_tokens(&mut inner);
+                append_braced(&mut inner, items_to_tokens(body), span);
+
+                append_braced(tokens, inner, span);
+            }
+            SnaxItem::Match { scrutinee, arms } => {
+                let span = first_span(scrutinee);
+
+                let mut arms_tokens = TokenStream::new();
+                for arm in arms {
+                    arm_to_tokens(arm, &mut arms_tokens);
+                }
+
+                let mut inner = TokenStream::new();
+                append_keyword(&mut inner, "match", span);
+                scrutinee.to_tokens(&mut inner);
+                append_braced(&mut inner, arms_tokens, span);
+
+                append_braced(tokens, inner, span);
+            }
+            SnaxItem::Let { pattern, init } => {
+                let span = first_span(pattern);
+
+                let mut inner = TokenStream::new();
+                append_keyword(&mut inner, "let", span);
+                pattern.to_tokens(&mut inner);
+                append_punct(&mut inner, "=", first_span(init));
+                init.to_tokens(&mut inner);
+                append_punct(&mut inner, ";", span);
+
+                append_braced(tokens, inner, span);
+            }
+            SnaxItem::Doctype { name } => {
+                append_punct(tokens, "<!", name.span());
+                append_keyword(tokens, "DOCTYPE", name.span());
+                name.to_tokens(tokens);
+                append_punct(tokens, ">", name.span());
+            }
+            SnaxItem::Comment(content) => {
+                append_punct(tokens, "<!--", content.span());
+                content.to_tokens(tokens);
+                append_punct(tokens, "-->", content.span());
+            }
+        }
+    }
+}
+
+fn items_to_tokens(items: &[SnaxItem]) -> TokenStream {
+    let mut tokens = TokenStream::new();
+    tokens.append_all(items);
+    tokens
+}
+
+/// Writes an `if` without the block around it, so that it can follow an
+/// `else` as well.
+fn if_to_tokens(item: &SnaxItem, tokens: &mut TokenStream) {
+    let (condition, then, else_) = match item {
+        SnaxItem::If {
+            condition,
+            then,
+            else_,
+        } => (condition, then, else_),
+        // An `else if` can only hold another `if`.
+        other => return other.to_tokens(tokens),
+    };
+    let span = first_span(condition);
+
+    append_keyword(tokens, "if", span);
+    condition.to_tokens(tokens);
+    append_braced(tokens, items_to_tokens(then), span);
+
+    match else_ {
+        Some(SnaxElse::If(else_if)) => {
+            append_keyword(tokens, "else", span);
+            if_to_tokens(else_if, tokens);
+        }
+        Some(SnaxElse::Block(else_)) => {
+            append_keyword(tokens, "else", span);
+            append_braced(tokens, items_to_tokens(else_), span);
+        }
+        None => {}
+    }
+}
+
+fn arm_to_tokens(arm: &SnaxMatchArm, tokens: &mut TokenStream) {
+    let span = first_span(&arm.pattern);
+
+    arm.pattern.to_tokens(tokens);
+    if let Some(guard) = &arm.guard {
+        append_keyword(tokens, "if", first_span(guard));
+        guard.to_tokens(tokens);
+    }
+    append_punct(tokens, "=>", span);
+    append_braced(tokens, items_to_tokens(&arm.body), span);
+    append_punct(tokens, ",", span);
+}
@@ -1,6 +1,6 @@
-use proc_macro2::{Ident, TokenTree};
+use proc_macro2::{Delimiter, Group, Ident, Spacing, Span, TokenStream, TokenTree};
 
-use crate::SnaxAttribute;
+use crate::{SnaxAttribute, SnaxName, SnaxPath, SnaxPathSegment, SnaxTagName};
 
 #[derive(Debug)]
 pub enum HtmlToken {
@@ -8,22 +8,42 @@ pub enum HtmlToken {
     CloseTag(HtmlCloseToken),
     SelfClosingTag(HtmlSelfClosingToken),
     Textish(HtmlTextishToken),
+    OpenFragment(HtmlFragmentToken),
+    CloseFragment(HtmlFragmentToken),
+    Doctype(HtmlDoctypeToken),
+    Comment(HtmlCommentToken),
+}
+
+impl HtmlToken {
+    /// The span of the token's name, or of the content for textish tokens.
+    pub fn span(&self) -> Span {
+        match self {
+            HtmlToken::OpenTag(token) => token.name.span(),
+            HtmlToken::CloseTag(token) => token.name.span(),
+            HtmlToken::SelfClosingTag(token) => token.name.span(),
+            HtmlToken::Textish(token) => token.content.span(),
+            HtmlToken::OpenFragment(token) => token.span,
+            HtmlToken::CloseFragment(token) => token.span,
+            HtmlToken::Doctype(token) => token.name.span(),
+            HtmlToken::Comment(token) => token.content.span(),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct HtmlOpenToken {
-    pub name: Ident,
+    pub name: SnaxTagName,
     pub attributes: Vec<SnaxAttribute>,
 }
 
 #[derive(Debug, Clone)]
 pub struct HtmlCloseToken {
-    pub name: Ident,
+    pub name: SnaxTagName,
 }
 
 #[derive(Debug)]
 pub struct HtmlSelfClosingToken {
-    pub name: Ident,
+    pub name: SnaxTagName,
     pub attributes: Vec<SnaxAttribute>,
 }
 
@@ -32,17 +52,64 @@ pub struct HtmlTextishToken {
     pub content: TokenTree,
 }
 
+/// Either half of a fragment, `<>` or `</>`.
+#[derive(Debug, Clone)]
+pub struct HtmlFragmentToken {
+    /// The span of the leading `<`.
+    pub span: Span,
+}
+
+/// `<!DOCTYPE html>`
+#[derive(Debug)]
+pub struct HtmlDoctypeToken {
+    pub name: Ident,
+}
+
+/// `<!-- "text" -->` or `<!-- {expression} -->`
+#[derive(Debug)]
+pub struct HtmlCommentToken {
+    pub content: TokenTree,
+}
+
 #[derive(Debug)]
 pub enum TokenizeError {
-    UnexpectedEnd,
+    /// The input ran out in the middle of a token. The span is the one of the
+    /// last token that was read, if any.
+    UnexpectedEnd(Span),
     UnexpectedToken(TokenTree),
 }
 
+/// Wraps the input iterator, remembering the span of the last token so that
+/// running out of input can be reported next to it.
+struct Tokens<I> {
+    inner: I,
+    last_span: Span,
+}
+
+impl<I: Iterator<Item = TokenTree>> Tokens<I> {
+    fn new(inner: I) -> Self {
+        Tokens {
+            inner,
+            last_span: Span::call_site(),
+        }
+    }
+
+    fn next(&mut self) -> Result<TokenTree, TokenizeError> {
+        match self.inner.next() {
+            Some(token) => {
+                self.last_span = token.span();
+                Ok(token)
+            }
+            None => Err(TokenizeError::UnexpectedEnd(self.last_span)),
+        }
+    }
+}
+
 /// Grabs the next item of the iterator, handling the None case, and then makes
 /// sure the given pattern matches.
 macro_rules! expect_next {
     ($iterator: expr, $pattern: pat $(if $guard: expr)? => $result: expr) => {
-        match $iterator.next().ok_or(TokenizeError::UnexpectedEnd)? {
+        match $iterator.next()? {
             $pattern $(if $guard)? => $result,
             unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
         }
@@ -52,31 +119,275 @@ macro_rules! expect_next {
     };
 }
 
+/// Reads the rest of a name whose first part has already been read, like the
+/// `-id` in `data-id` or the `:href` in `xlink:href`. `next` is the token
+/// after the first part.
+///
+/// Finding the end of the name means reading one token past it, so that token
+/// is returned along with the name.
+fn parse_name(
+    first: Ident,
+    next: TokenTree,
+    input: &mut Tokens<impl Iterator<Item = TokenTree>>,
+) -> Result<(SnaxName, TokenTree), TokenizeError> {
+    let (parts, next) = parse_name_parts(first, next, input)?;
+
+    match next {
+        TokenTree::Punct(ref punct)
+            if punct.as_char() == ':' && punct.spacing() == Spacing::Alone =>
+        {
+            let first = expect_next!(input, TokenTree::Ident(part) => part);
+            let next = input.next()?;
+            let (local, next) = parse_name_parts(first, next, input)?;
+
+            let name = SnaxName {
+                namespace: Some(parts),
+                local,
+            };
+            Ok((name, next))
+        }
+        next => {
+            let name = SnaxName {
+                namespace: None,
+                local: parts,
+            };
+            Ok((name, next))
+        }
+    }
+}
+
+/// Reads the dashed parts of one half of a name, along with the token after
+/// them.
+fn parse_name_parts(
+    first: Ident,
+    mut next: TokenTree,
+    input: &mut Tokens<impl Iterator<Item = TokenTree>>,
+) -> Result<(Vec<Ident>, TokenTree), TokenizeError> {
+    let mut parts = vec![first];
+
+    loop {
+        match next {
+            TokenTree::Punct(ref punct) if punct.as_char() == '-' => {
+                parts.push(expect_next!(input, TokenTree::Ident(part) => part));
+                next = input.next()?;
+            }
+            next => return Ok((parts, next)),
+        }
+    }
+}
+
+/// Reads the rest of a tag name, which is either an element name or the path
+/// of a component, along with the token after it.
+///
+/// Names that start with an uppercase letter or are followed by `::` are
+/// components, so `<Button>` and `<ui::button>` are paths while `<button>`
+/// is an element.
+fn parse_tag_name(
+    first: Ident,
+    input: &mut Tokens<impl Iterator<Item = TokenTree>>,
+) -> Result<(SnaxTagName, TokenTree), TokenizeError> {
+    let is_component = first
+        .to_string()
+        .starts_with(|c: char| c.is_ascii_uppercase());
+    let next = input.next()?;
+
+    if is_component || is_path_separator(&next) {
+        let (path, next) = parse_path(first, next, input)?;
+        Ok((SnaxTagName::Path(path), next))
+    } else {
+        let (name, next) = parse_name(first, next, input)?;
+        Ok((SnaxTagName::Element(name), next))
+    }
+}
+
+/// Whether `token` is the first half of a `::`.
+fn is_path_separator(token: &TokenTree) -> bool {
+    match token {
+        TokenTree::Punct(punct) => punct.as_char() == ':' && punct.spacing() == Spacing::Joint,
+        _ => false,
+    }
+}
+
+/// Reads a component path like `ui::List<Row>` whose first segment has
+/// already been read, along with the token after it.
+fn parse_path(
+    first: Ident,
+    mut next: TokenTree,
+    input: &mut Tokens<impl Iterator<Item = TokenTree>>,
+) -> Result<(SnaxPath, TokenTree), TokenizeError> {
+    let mut segments = Vec::new();
+    let mut ident = first;
+
+    loop {
+        let arguments = match next {
+            TokenTree::Punct(ref punct) if punct.as_char() == '<' => {
+                let arguments = parse_generic_arguments(input)?;
+                next = input.next()?;
+                Some(arguments)
+            }
+            _ => None,
+        };
+
+        segments.push(SnaxPathSegment { ident, arguments });
+
+        if !is_path_separator(&next) {
+            return Ok((SnaxPath { segments }, next));
+        }
+
+        expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == ':');
+        ident = expect_next!(input, TokenTree::Ident(ident) => ident);
+        next = input.next()?;
+    }
+}
+
+/// Reads generic arguments up to the `>` that closes an already read `<`,
+/// keeping track of nested arguments like `Vec<Option<T>>`.
+fn parse_generic_arguments(
+    input: &mut Tokens<impl Iterator<Item = TokenTree>>,
+) -> Result<TokenStream, TokenizeError> {
+    let mut arguments: Vec<TokenTree> = Vec::new();
+    let mut depth = 0;
+
+    loop {
+        let token = input.next()?;
+
+        if let TokenTree::Punct(ref punct) = token {
+            // The `>` of a `->`, like in `Fn() -> T`, doesn't close anything.
+            let is_arrow = match arguments.last() {
+                Some(TokenTree::Punct(previous)) => {
+                    previous.as_char() == '-' && previous.spacing() == Spacing::Joint
+                }
+                _ => false,
+            };
+
+            match punct.as_char() {
+                '<' => depth += 1,
+                '>' if !is_arrow && depth == 0 => return Ok(arguments.into_iter().collect()),
+                '>' if !is_arrow => depth -= 1,
+                _ => {}
+            }
+        }
+
+        arguments.push(token);
+    }
+}
+
+/// Parses a braced group in attribute position, which is either a spread,
+/// `{..props}`, or a shorthand, `{title}`.
+pub fn parse_block_attribute(group: Group) -> Result<SnaxAttribute, TokenizeError> {
+    if group.delimiter() == Delimiter::Brace {
+        let mut tokens = group.stream().into_iter();
+
+        match (tokens.next(), tokens.next()) {
+            (Some(TokenTree::Punct(ref first)), Some(TokenTree::Punct(ref second)))
+                if first.as_char() == '.' && second.as_char() == '.' =>
+            {
+                let props: TokenStream = tokens.collect();
+
+                // `{...props}` and `{..=props}` aren't spreads, and would
+                // only fail later with an unrelated error.
+                if let Some(TokenTree::Punct(punct)) = props.clone().into_iter().next() {
+                    if punct.as_char() == '.' || punct.as_char() == '=' {
+                        return Err(TokenizeError::UnexpectedToken(TokenTree::Punct(punct)));
+                    }
+                }
+
+                if !props.is_empty() {
+                    return Ok(SnaxAttribute::Spread(props));
+                }
+            }
+            (Some(TokenTree::Ident(name)), None) => {
+                return Ok(SnaxAttribute::Shorthand { name });
+            }
+            _ => {}
+        }
+    }
+
+    Err(TokenizeError::UnexpectedToken(TokenTree::Group(group)))
+}
+
 pub fn parse_html_token(
-    mut input: impl Iterator<Item = TokenTree>,
+    input: impl Iterator<Item = TokenTree>,
 ) -> Result<HtmlToken, TokenizeError> {
-    match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
+    let mut input = Tokens::new(input);
+
+    match input.next()? {
         TokenTree::Punct(ref punct) if punct.as_char() == '<' => {
-            match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
-                TokenTree::Punct(ref punct) if punct.as_char() == '/' => {
-                    match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
-                        TokenTree::Ident(name) => {
+            let span = punct.span();
+
+            match input.next()? {
+                TokenTree::Punct(ref punct) if punct.as_char() == '>' => {
+                    Ok(HtmlToken::OpenFragment(HtmlFragmentToken { span }))
+                }
+                TokenTree::Punct(ref punct) if punct.as_char() == '!' => match input.next()? {
+                    TokenTree::Ident(ref keyword)
+                        if keyword.to_string().eq_ignore_ascii_case("doctype") =>
+                    {
+                        let name = expect_next!(input, TokenTree::Ident(name) => name);
+                        expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '>');
+
+                        Ok(HtmlToken::Doctype(HtmlDoctypeToken { name }))
+                    }
+                    TokenTree::Punct(ref punct) if punct.as_char() == '-' => {
+                        expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '-');
+
+                        let content = match input.next()? {
+                            content @ TokenTree::Literal(_) | content @ TokenTree::Group(_) => {
+                                content
+                            }
+                            unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
+                        };
+
+                        expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '-');
+                        expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '-');
+                        expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '>');
+
+                        Ok(HtmlToken::Comment(HtmlCommentToken { content }))
+                    }
+                    unexpected => Err(TokenizeError::UnexpectedToken(unexpected)),
+                },
+                TokenTree::Punct(ref punct) if punct.as_char() == '/' => match input.next()? {
+                    TokenTree::Punct(ref punct) if punct.as_char() == '>' => {
+                        Ok(HtmlToken::CloseFragment(HtmlFragmentToken { span }))
+                    }
+                    TokenTree::Ident(name) => {
+                        let (name, next) = parse_tag_name(name, &mut input)?;
 
-                            expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '>');
-                            Ok(HtmlToken::CloseTag(HtmlCloseToken { name }))
+                        match next {
+                            TokenTree::Punct(ref punct) if punct.as_char() == '>' => {
+                                Ok(HtmlToken::CloseTag(HtmlCloseToken { name }))
+                            }
+                            unexpected => Err(TokenizeError::UnexpectedToken(unexpected)),
                         }
-                        unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
                     }
-                }
+                    unexpected => Err(TokenizeError::UnexpectedToken(unexpected)),
+                },
 
                 TokenTree::Ident(name) => {
+                    let (name, mut next) = parse_tag_name(name, &mut input)?;
                     let mut attributes = Vec::new();
                     loop {
-                        match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
+                        match next {
                             TokenTree::Ident(attribute_name) => {
-                                expect_next!(input, TokenTree::Punct(ref punct) if punct.as_char() == '=');
+                                let after_name = input.next()?;
+                                let (attribute_name, after_name) =
+                                    parse_name(attribute_name, after_name, &mut input)?;
 
-                                match input.next().ok_or(TokenizeError::UnexpectedEnd)? {
+                                match after_name {
+                                    TokenTree::Punct(ref punct) if punct.as_char() == '=' => {}
+                                    after_name => {
+                                        // Boolean attribute, so the token after
+                                        // the name starts the next attribute.
+
+                                        attributes.push(SnaxAttribute::Boolean {
+                                            name: attribute_name,
+                                        });
+                                        next = after_name;
+                                        continue;
+                                    }
+                                }
+
+                                match input.next()? {
                                     value @ TokenTree::Literal(_) | value @ TokenTree::Group(_) => {
                                         attributes.push(SnaxAttribute::Simple {
                                             name: attribute_name,
@@ -88,6 +399,9 @@ pub fn parse_html_token(
                                     }
                                 }
                             }
+                            TokenTree::Group(group) => {
+                                attributes.push(parse_block_attribute(group)?);
+                            }
                             TokenTree::Punct(ref punct) if punct.as_char() == '>' => {
                                 // Opening tag
 
@@ -105,13 +419,15 @@ pub fn parse_html_token(
                             }
                             unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
                         }
+
+                        next = input.next()?;
                     }
                 }
-                unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
+                unexpected => Err(TokenizeError::UnexpectedToken(unexpected)),
             }
         }
         content @ TokenTree::Literal(_) => Ok(HtmlToken::Textish(HtmlTextishToken { content })),
         content @ TokenTree::Group(_) => Ok(HtmlToken::Textish(HtmlTextishToken { content })),
-        unexpected => return Err(TokenizeError::UnexpectedToken(unexpected)),
+        unexpected => Err(TokenizeError::UnexpectedToken(unexpected)),
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,225 @@
+//! Walks a borrowed syntax tree, in the style of `syn::visit`.
+//!
+//! Each method of [`Visit`] is called for one type of node and by default
+//! calls the free function of the same name, which visits the node's
+//! children. Overriding a method and calling the function from it keeps the
+//! walk going, leaving the call out skips the node's children.
+//!
+//! ```ignore
+//! struct CountImages(usize);
+//!
+//! impl<'ast> Visit<'ast> for CountImages {
+//!     fn visit_tag_name(&mut self, name: &'ast SnaxTagName) {
+//!         if let SnaxTagName::Element(name) = name {
+//!             if name == "img" {
+//!                 self.0 += 1;
+//!             }
+//!         }
+//!     }
+//! }
+//! ```
+//!
+//! [`Visit`]: trait.Visit.html
+
+use proc_macro2::{Ident, TokenStream, TokenTree};
+
+use crate::{
+    SnaxAttribute, SnaxElse, SnaxItem, SnaxMatchArm, SnaxName, SnaxPath, SnaxSelfClosingTag,
+    SnaxTag, SnaxTagName,
+};
+
+/// Visits the nodes of a syntax tree by reference. See the [module docs].
+///
+/// [module docs]: index.html
+pub trait Visit<'ast> {
+    fn visit_item(&mut self, item: &'ast SnaxItem) {
+        visit_item(self, item)
+    }
+
+    fn visit_tag(&mut self, tag: &'ast SnaxTag) {
+        visit_tag(self, tag)
+    }
+
+    fn visit_self_closing_tag(&mut self, tag: &'ast SnaxSelfClosingTag) {
+        visit_self_closing_tag(self, tag)
+    }
+
+    fn visit_tag_name(&mut self, name: &'ast SnaxTagName) {
+        visit_tag_name(self, name)
+    }
+
+    fn visit_name(&mut self, name: &'ast SnaxName) {
+        visit_name(self, name)
+    }
+
+    fn visit_path(&mut self, path: &'ast SnaxPath) {
+        visit_path(self, path)
+    }
+
+    fn visit_attribute(&mut self, attribute: &'ast SnaxAttribute) {
+        visit_attribute(self, attribute)
+    }
+
+    /// Called for the literal or expression of a `SnaxAttribute::Simple`.
+    fn visit_attribute_value(&mut self, value: &'ast TokenTree) {
+        visit_attribute_value(self, value)
+    }
+
+    /// Called for the expression of a `SnaxAttribute::Spread`.
+    fn visit_spread(&mut self, props: &'ast TokenStream) {
+        visit_spread(self, props)
+    }
+
+    /// Called for the variable of a `SnaxAttribute::Shorthand`.
+    fn visit_shorthand(&mut self, name: &'ast Ident) {
+        visit_shorthand(self, name)
+    }
+
+    /// Called for the expression or literal of a `SnaxItem::Content`.
+    fn visit_content(&mut self, content: &'ast TokenTree) {
+        visit_content(self, content)
+    }
+
+    /// Called for the literal or expression inside a `SnaxItem::Comment`.
+    fn visit_comment(&mut self, content: &'ast TokenTree) {
+        visit_comment(self, content)
+    }
+
+    /// Called for the pattern and initializer of a `SnaxItem::Let`.
+    fn visit_let(&mut self, pattern: &'ast TokenStream, init: &'ast TokenStream) {
+        visit_let(self, pattern, init)
+    }
+
+    /// Called for the name of a `SnaxItem::Doctype`, like `html`.
+    fn visit_doctype(&mut self, name: &'ast Ident) {
+        visit_doctype(self, name)
+    }
+
+    fn visit_else(&mut self, else_: &'ast SnaxElse) {
+        visit_else(self, else_)
+    }
+
+    fn visit_match_arm(&mut self, arm: &'ast SnaxMatchArm) {
+        visit_match_arm(self, arm)
+    }
+}
+
+pub fn visit_item<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, item: &'ast SnaxItem) {
+    match item {
+        SnaxItem::Tag(tag) => visitor.visit_tag(tag),
+        SnaxItem::SelfClosingTag(tag) => visitor.visit_self_closing_tag(tag),
+        SnaxItem::Content(content) => visitor.visit_content(content),
+        SnaxItem::Fragment(children) => {
+            for child in children {
+                visitor.visit_item(child);
+            }
+        }
+        SnaxItem::If { then, else_, .. } => {
+            for item in then {
+                visitor.visit_item(item);
+            }
+            if let Some(else_) = else_ {
+                visitor.visit_else(else_);
+            }
+        }
+        SnaxItem::For { body, .. } => {
+            for item in body {
+                visitor.visit_item(item);
+            }
+        }
+        SnaxItem::Match { arms, .. } => {
+            for arm in arms {
+                visitor.visit_match_arm(arm);
+            }
+        }
+        SnaxItem::Let { pattern, init } => visitor.visit_let(pattern, init),
+        SnaxItem::Doctype { name } => visitor.visit_doctype(name),
+        SnaxItem::Comment(content) => visitor.visit_comment(content),
+    }
+}
+
+pub fn visit_tag<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, tag: &'ast SnaxTag) {
+    visitor.visit_tag_name(&tag.name);
+    for attribute in &tag.attributes {
+        visitor.visit_attribute(attribute);
+    }
+    for child in &tag.children {
+        visitor.visit_item(child);
+    }
+}
+
+pub fn visit_self_closing_tag<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    tag: &'ast SnaxSelfClosingTag,
+) {
+    visitor.visit_tag_name(&tag.name);
+    for attribute in &tag.attributes {
+        visitor.visit_attribute(attribute);
+    }
+}
+
+pub fn visit_tag_name<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, name: &'ast SnaxTagName) {
+    match name {
+        SnaxTagName::Element(name) => visitor.visit_name(name),
+        SnaxTagName::Path(path) => visitor.visit_path(path),
+    }
+}
+
+pub fn visit_name<'ast, V: Visit<'ast> + ?Sized>(_visitor: &mut V, _name: &'ast SnaxName) {}
+
+pub fn visit_path<'ast, V: Visit<'ast> + ?Sized>(_visitor: &mut V, _path: &'ast SnaxPath) {}
+
+pub fn visit_attribute<'ast, V: Visit<'ast> + ?Sized>(
+    visitor: &mut V,
+    attribute: &'ast SnaxAttribute,
+) {
+    match attribute {
+        SnaxAttribute::Simple { name, value } => {
+            visitor.visit_name(name);
+            visitor.visit_attribute_value(value);
+        }
+        SnaxAttribute::Boolean { name } => visitor.visit_name(name),
+        SnaxAttribute::Spread(props) => visitor.visit_spread(props),
+        SnaxAttribute::Shorthand { name } => visitor.visit_shorthand(name),
+    }
+}
+
+pub fn visit_attribute_value<'ast, V: Visit<'ast> + ?Sized>(
+    _visitor: &mut V,
+    _value: &'ast TokenTree,
+) {
+}
+
+pub fn visit_spread<'ast, V: Visit<'ast> + ?Sized>(_visitor: &mut V, _props: &'ast TokenStream) {}
+
+pub fn visit_shorthand<'ast, V: Visit<'ast> + ?Sized>(_visitor: &mut V, _name: &'ast Ident) {}
+
+pub fn visit_content<'ast, V: Visit<'ast> + ?Sized>(_visitor: &mut V, _content: &'ast TokenTree) {}
+
+pub fn visit_comment<'ast, V: Visit<'ast> + ?Sized>(_visitor: &mut V, _content: &'ast TokenTree) {}
+
+pub fn visit_let<'ast, V: Visit<'ast> + ?Sized>(
+    _visitor: &mut V,
+    _pattern: &'ast TokenStream,
+    _init: &'ast TokenStream,
+) {
+}
+
+pub fn visit_doctype<'ast, V: Visit<'ast> + ?Sized>(_visitor: &mut V, _name: &'ast Ident) {}
+
+pub fn visit_else<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, else_: &'ast SnaxElse) {
+    match else_ {
+        SnaxElse::If(item) => visitor.visit_item(item),
+        SnaxElse::Block(items) => {
+            for item in items {
+                visitor.visit_item(item);
+            }
+        }
+    }
+}
+
+pub fn visit_match_arm<'ast, V: Visit<'ast> + ?Sized>(visitor: &mut V, arm: &'ast SnaxMatchArm) {
+    for item in &arm.body {
+        visitor.visit_item(item);
+    }
+}
@@ -0,0 +1,205 @@
+//! Walks a syntax tree by mutable reference, in the style of
+//! `syn::visit_mut`, so that nodes can be changed in place.
+//!
+//! It works like [`visit`], with every method and function name ending in
+//! `_mut`. To replace a node with any number of nodes, use [`fold`] instead.
+//!
+//! [`visit`]: ../visit/index.html
+//! [`fold`]: ../fold/index.html
+
+use proc_macro2::{Ident, TokenStream, TokenTree};
+
+use crate::{
+    SnaxAttribute, SnaxElse, SnaxItem, SnaxMatchArm, SnaxName, SnaxPath, SnaxSelfClosingTag,
+    SnaxTag, SnaxTagName,
+};
+
+/// Visits the nodes of a syntax tree by mutable reference. See the
+/// [module docs].
+///
+/// [module docs]: index.html
+pub trait VisitMut {
+    fn visit_item_mut(&mut self, item: &mut SnaxItem) {
+        visit_item_mut(self, item)
+    }
+
+    fn visit_tag_mut(&mut self, tag: &mut SnaxTag) {
+        visit_tag_mut(self, tag)
+    }
+
+    fn visit_self_closing_tag_mut(&mut self, tag: &mut SnaxSelfClosingTag) {
+        visit_self_closing_tag_mut(self, tag)
+    }
+
+    fn visit_tag_name_mut(&mut self, name: &mut SnaxTagName) {
+        visit_tag_name_mut(self, name)
+    }
+
+    fn visit_name_mut(&mut self, name: &mut SnaxName) {
+        visit_name_mut(self, name)
+    }
+
+    fn visit_path_mut(&mut self, path: &mut SnaxPath) {
+        visit_path_mut(self, path)
+    }
+
+    fn visit_attribute_mut(&mut self, attribute: &mut SnaxAttribute) {
+        visit_attribute_mut(self, attribute)
+    }
+
+    /// Called for the literal or expression of a `SnaxAttribute::Simple`.
+    fn visit_attribute_value_mut(&mut self, value: &mut TokenTree) {
+        visit_attribute_value_mut(self, value)
+    }
+
+    /// Called for the expression of a `SnaxAttribute::Spread`.
+    fn visit_spread_mut(&mut self, props: &mut TokenStream) {
+        visit_spread_mut(self, props)
+    }
+
+    /// Called for the variable of a `SnaxAttribute::Shorthand`.
+    fn visit_shorthand_mut(&mut self, name: &mut Ident) {
+        visit_shorthand_mut(self, name)
+    }
+
+    /// Called for the expression or literal of a `SnaxItem::Content`.
+    fn visit_content_mut(&mut self, content: &mut TokenTree) {
+        visit_content_mut(self, content)
+    }
+
+    /// Called for the literal or expression inside a `SnaxItem::Comment`.
+    fn visit_comment_mut(&mut self, content: &mut TokenTree) {
+        visit_comment_mut(self, content)
+    }
+
+    /// Called for the pattern and initializer of a `SnaxItem::Let`.
+    fn visit_let_mut(&mut self, pattern: &mut TokenStream, init: &mut TokenStream) {
+        visit_let_mut(self, pattern, init)
+    }
+
+    /// Called for the name of a `SnaxItem::Doctype`, like `html`.
+    fn visit_doctype_mut(&mut self, name: &mut Ident) {
+        visit_doctype_mut(self, name)
+    }
+
+    fn visit_else_mut(&mut self, else_: &mut SnaxElse) {
+        visit_else_mut(self, else_)
+    }
+
+    fn visit_match_arm_mut(&mut self, arm: &mut SnaxMatchArm) {
+        visit_match_arm_mut(self, arm)
+    }
+}
+
+pub fn visit_item_mut<V: VisitMut + ?Sized>(visitor: &mut V, item: &mut SnaxItem) {
+    match item {
+        SnaxItem::Tag(tag) => visitor.visit_tag_mut(tag),
+        SnaxItem::SelfClosingTag(tag) => visitor.visit_self_closing_tag_mut(tag),
+        SnaxItem::Content(content) => visitor.visit_content_mut(content),
+        SnaxItem::Fragment(children) => {
+            for child in children {
+                visitor.visit_item_mut(child);
+            }
+        }
+        SnaxItem::If { then, else_, .. } => {
+            for item in then {
+                visitor.visit_item_mut(item);
+            }
+            if let Some(else_) = else_ {
+                visitor.visit_else_mut(else_);
+            }
+        }
+        SnaxItem::For { body, .. } => {
+            for item in body {
+                visitor.visit_item_mut(item);
+            }
+        }
+        SnaxItem::Match { arms, .. } => {
+            for arm in arms {
+                visitor.visit_match_arm_mut(arm);
+            }
+        }
+        SnaxItem::Let { pattern, init } => visitor.visit_let_mut(pattern, init),
+        SnaxItem::Doctype { name } => visitor.visit_doctype_mut(name),
+        SnaxItem::Comment(content) => visitor.visit_comment_mut(content),
+    }
+}
+
+pub fn visit_tag_mut<V: VisitMut + ?Sized>(visitor: &mut V, tag: &mut SnaxTag) {
+    visitor.visit_tag_name_mut(&mut tag.name);
+    for attribute in &mut tag.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+    for child in &mut tag.children {
+        visitor.visit_item_mut(child);
+    }
+}
+
+pub fn visit_self_closing_tag_mut<V: VisitMut + ?Sized>(
+    visitor: &mut V,
+    tag: &mut SnaxSelfClosingTag,
+) {
+    visitor.visit_tag_name_mut(&mut tag.name);
+    for attribute in &mut tag.attributes {
+        visitor.visit_attribute_mut(attribute);
+    }
+}
+
+pub fn visit_tag_name_mut<V: VisitMut + ?Sized>(visitor: &mut V, name: &mut SnaxTagName) {
+    match name {
+        SnaxTagName::Element(name) => visitor.visit_name_mut(name),
+        SnaxTagName::Path(path) => visitor.visit_path_mut(path),
+    }
+}
+
+pub fn visit_name_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _name: &mut SnaxName) {}
+
+pub fn visit_path_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _path: &mut SnaxPath) {}
+
+pub fn visit_attribute_mut<V: VisitMut + ?Sized>(visitor: &mut V, attribute: &mut SnaxAttribute) {
+    match attribute {
+        SnaxAttribute::Simple { name, value } => {
+            visitor.visit_name_mut(name);
+            visitor.visit_attribute_value_mut(value);
+        }
+        SnaxAttribute::Boolean { name } => visitor.visit_name_mut(name),
+        SnaxAttribute::Spread(props) => visitor.visit_spread_mut(props),
+        SnaxAttribute::Shorthand { name } => visitor.visit_shorthand_mut(name),
+    }
+}
+
+pub fn visit_attribute_value_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _value: &mut TokenTree) {}
+
+pub fn visit_spread_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _props: &mut TokenStream) {}
+
+pub fn visit_shorthand_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _name: &mut Ident) {}
+
+pub fn visit_content_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _content: &mut TokenTree) {}
+
+pub fn visit_comment_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _content: &mut TokenTree) {}
+
+pub fn visit_let_mut<V: VisitMut + ?Sized>(
+    _visitor: &mut V,
+    _pattern: &mut TokenStream,
+    _init: &mut TokenStream,
+) {
+}
+
+pub fn visit_doctype_mut<V: VisitMut + ?Sized>(_visitor: &mut V, _name: &mut Ident) {}
+
+pub fn visit_else_mut<V: VisitMut + ?Sized>(visitor: &mut V, else_: &mut SnaxElse) {
+    match else_ {
+        SnaxElse::If(item) => visitor.visit_item_mut(item),
+        SnaxElse::Block(items) => {
+            for item in items {
+                visitor.visit_item_mut(item);
+            }
+        }
+    }
+}
+
+pub fn visit_match_arm_mut<V: VisitMut + ?Sized>(visitor: &mut V, arm: &mut SnaxMatchArm) {
+    for item in &mut arm.body {
+        visitor.visit_item_mut(item);
+    }
+}
@@ -0,0 +1,152 @@
+use proc_macro2::{Literal, TokenStream, TokenTree};
+use quote::quote;
+
+use rust_jsx::fold::{self, Fold};
+use rust_jsx::{SnaxAttribute, SnaxElse, SnaxItem};
+
+/// Replaces every `<icon name="x" />` with the `svg` it stands for.
+struct InlineIcons;
+
+impl Fold for InlineIcons {
+    fn fold_item(&mut self, item: SnaxItem) -> Vec<SnaxItem> {
+        match item {
+            SnaxItem::SelfClosingTag(ref tag) if tag.name.to_string() == "icon" => {
+                let name = tag.attributes.iter().find_map(|attribute| match attribute {
+                    SnaxAttribute::Simple { name, value } if name == "name" => Some(value),
+                    _ => None,
+                });
+
+                vec![rust_jsx::parse(quote!(<svg><use href={#name} /></svg>)).unwrap()]
+            }
+            item => vec![fold::fold_item(self, item)],
+        }
+    }
+}
+
+#[test]
+fn replace_item() {
+    let item = rust_jsx::parse(quote! {
+        <button>
+            {if saving { <icon name="spinner" /> }}
+            "Save"
+        </button>
+    })
+    .unwrap();
+
+    assert_eq!(
+        InlineIcons.fold_item(item),
+        [rust_jsx::parse(quote! {
+            <button>
+                {if saving { <svg><use href={"spinner"} /></svg> }}
+                "Save"
+            </button>
+        })
+        .unwrap()]
+    );
+}
+
+/// Drops comments and splits `<pair />` into two `<half />`s.
+struct Reshape;
+
+impl Fold for Reshape {
+    fn fold_item(&mut self, item: SnaxItem) -> Vec<SnaxItem> {
+        match item {
+            SnaxItem::Comment(_) => vec![],
+            SnaxItem::SelfClosingTag(ref tag) if tag.name.to_string() == "pair" => {
+                rust_jsx::parse_many(quote!(<half /> <half />)).unwrap()
+            }
+            item => vec![fold::fold_item(self, item)],
+        }
+    }
+}
+
+#[test]
+fn replace_item_with_zero_or_many() {
+    let item = rust_jsx::parse(quote! {
+        <div>
+            <!-- "gone" -->
+            <pair />
+            <>{for x in xs { <pair /> }}</>
+        </div>
+    })
+    .unwrap();
+
+    assert_eq!(
+        Reshape.fold_items(vec![item]),
+        [rust_jsx::parse(quote! {
+            <div>
+                <half /> <half />
+                <>{for x in xs { <half /> <half /> }}</>
+            </div>
+        })
+        .unwrap()]
+    );
+}
+
+/// Replaces every `if` with its `then` branch.
+struct AlwaysThen;
+
+impl Fold for AlwaysThen {
+    fn fold_item(&mut self, item: SnaxItem) -> Vec<SnaxItem> {
+        match item {
+            SnaxItem::If { then, .. } => self.fold_items(then),
+            item => vec![fold::fold_item(self, item)],
+        }
+    }
+}
+
+#[test]
+fn replaced_else_if_becomes_else_block() {
+    let item = rust_jsx::parse(quote! {
+        <p>{if a { "a" } else if b { "b" } else { "c" }}</p>
+    })
+    .unwrap();
+
+    let else_ = match item {
+        SnaxItem::Tag(tag) => match tag.children.into_iter().next() {
+            Some(SnaxItem::If { else_, .. }) => else_.unwrap(),
+            other => panic!("expected an if, got {:?}", other),
+        },
+        other => panic!("expected a tag, got {:?}", other),
+    };
+
+    assert_eq!(
+        AlwaysThen.fold_else(else_),
+        SnaxElse::Block(vec![SnaxItem::Content(
+            quote!("b").into_iter().next().unwrap()
+        )])
+    );
+}
+
+/// Uppercases comments and replaces `let` initializers with a default.
+struct Rewrite;
+
+impl Fold for Rewrite {
+    fn fold_comment(&mut self, content: TokenTree) -> TokenTree {
+        TokenTree::Literal(Literal::string(
+            &content.to_string().trim_matches('"').to_uppercase(),
+        ))
+    }
+
+    fn fold_let(&mut self, pattern: TokenStream, _init: TokenStream) -> (TokenStream, TokenStream) {
+        (pattern, quote!(Default::default()))
+    }
+}
+
+#[test]
+fn fold_leaves() {
+    let items = rust_jsx::parse_many(quote! {
+        <!-- "note" -->
+        <p>{let x = 1;} {x}</p>
+    })
+    .unwrap();
+
+    assert_eq!(
+        Rewrite.fold_items(items),
+        rust_jsx::parse_many(quote! {
+            <!-- "NOTE" -->
+            <p>{let x = Default::default();} {x}</p>
+        })
+        .unwrap()
+    );
+}
@@ -0,0 +1,180 @@
+use std::fmt;
+use std::io;
+
+use rust_jsx::render::{self, Context, Output, Raw, Render};
+
+#[test]
+fn escapes_text() {
+    assert_eq!(
+        "<a href=\"x\">Tom & 'Jerry'</a>".render_to_string(),
+        "&lt;a href=&quot;x&quot;&gt;Tom &amp; &#39;Jerry&#39;&lt;/a&gt;"
+    );
+    assert_eq!("ünïcödé & ✓".render_to_string(), "ünïcödé &amp; ✓");
+}
+
+#[test]
+fn numbers_and_containers() {
+    assert_eq!(42u8.render_to_string(), "42");
+    assert_eq!((-1.5f64).render_to_string(), "-1.5");
+    assert_eq!(Some("x").render_to_string(), "x");
+    assert_eq!(None::<String>.render_to_string(), "");
+    assert_eq!(vec![1, 2, 3].render_to_string(), "123");
+    assert_eq!(("a", 1, '<').render_to_string(), "a1&lt;");
+    assert_eq!(Box::new(String::from("&")).render_to_string(), "&amp;");
+}
+
+#[test]
+fn iterators_and_closures() {
+    let names = ["Ann", "Bob"];
+    let list = render::iter(names.iter().map(|name| (name, ";")));
+
+    // Rendering twice works, since the iterator is cloned.
+    assert_eq!(list.render_to_string(), "Ann;Bob;");
+    assert_eq!(list.render_to_string(), "Ann;Bob;");
+
+    let bold = render::from_fn(|out| {
+        out.write_markup("<b>")?;
+        out.write_text("<&>")?;
+        out.write_markup("</b>")
+    });
+    assert_eq!(bold.render_to_string(), "<b>&lt;&amp;&gt;</b>");
+}
+
+#[test]
+fn renders_into_fmt_write() {
+    struct Page;
+
+    impl fmt::Display for Page {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            ("<", 1).render_to_fmt(f)
+        }
+    }
+
+    assert_eq!(Page.to_string(), "&lt;1");
+}
+
+#[test]
+fn renders_into_io_write() {
+    let mut bytes = Vec::new();
+    vec!["a&", "b"].render_to_io(&mut bytes).unwrap();
+
+    assert_eq!(bytes, b"a&amp;b");
+}
+
+#[test]
+fn io_errors_are_kept() {
+    struct Full;
+
+    impl io::Write for Full {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::WriteZero, "full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let error = "text".render_to_io(&mut Full).unwrap_err();
+    assert_eq!(error.kind(), io::ErrorKind::WriteZero);
+}
+
+#[test]
+fn errors_from_render_are_returned() {
+    struct Broken;
+
+    impl Render for Broken {
+        fn render(&self, _: &mut Output) -> fmt::Result {
+            Err(fmt::Error)
+        }
+    }
+
+    let mut string = String::new();
+    assert!((1, Broken).render_to_fmt(&mut string).is_err());
+    assert_eq!(string, "1");
+}
+
+#[test]
+fn escapes_for_context() {
+    fn render_in(context: Context, value: &dyn Render) -> String {
+        let mut string = String::new();
+        Output::new(&mut string)
+            .with_context(context, |out| value.render(out))
+            .unwrap();
+        string
+    }
+
+    assert_eq!(
+        render_in(Context::Attribute, &"\"a\" & <b>"),
+        "&quot;a&quot; &amp; &lt;b&gt;"
+    );
+    assert_eq!(
+        render_in(Context::Script, &"\"</script>\\"),
+        r#""\x22\x3C/script\x3E\\""#
+    );
+    assert_eq!(
+        render_in(Context::Script, &"alert(document.cookie)"),
+        r#""alert(document.cookie)""#
+    );
+    assert_eq!(render_in(Context::Script, &("a", 1)), r#""a1""#);
+    assert_eq!(render_in(Context::Script, &""), "\"\"");
+    assert_eq!(
+        render_in(Context::Style, &"'</style>"),
+        r"\27 \3c /style\3e "
+    );
+}
+
+#[test]
+fn unsafe_urls_are_replaced() {
+    assert_eq!(render::attribute_context("href"), Context::Url);
+    assert_eq!(render::attribute_context("title"), Context::Attribute);
+
+    fn render_url(value: &dyn Render) -> String {
+        let mut string = String::new();
+        Output::new(&mut string)
+            .with_context(Context::Url, |out| value.render(out))
+            .unwrap();
+        string
+    }
+
+    assert_eq!(
+        render_url(&"https://a.com/?x=1&y=2"),
+        "https://a.com/?x=1&amp;y=2"
+    );
+    assert_eq!(render_url(&"/about#team"), "/about#team");
+    assert_eq!(render_url(&("mailto:", "me@a.com")), "mailto:me@a.com");
+    assert_eq!(render_url(&"javascript:alert(1)"), "about:invalid");
+    assert_eq!(render_url(&" JaVa\tScript:alert(1)"), "about:invalid");
+    assert_eq!(render_url(&Raw("data:text/html,<p>")), "about:invalid");
+}
+
+#[test]
+fn raw_is_not_escaped() {
+    assert_eq!(("<", Raw("<br>")).render_to_string(), "&lt;<br>");
+
+    let mut string = String::new();
+    let mut out = Output::new(&mut string);
+    out.with_context(Context::Script, |out| Raw("\"").render(out))
+        .unwrap();
+    assert_eq!(out.context(), Context::Text);
+    out.write_text("\"").unwrap();
+    out.with_context(Context::Script, |out| ("a", Raw("+b")).render(out))
+        .unwrap();
+    assert_eq!(string, "\"&quot;\"a\"+b");
+}
+
+#[test]
+fn size_hints() {
+    assert_eq!("ab".size_hint(), 2);
+    assert_eq!(Some(String::from("abc")).size_hint(), 3);
+    assert_eq!(vec!["a", "bc"].size_hint(), 3);
+    assert_eq!(("a", Raw("<br>"), 1).size_hint(), 5);
+    assert_eq!(Box::new(&"abc").size_hint(), 3);
+
+    let string = ("ab", "c").render_to_string();
+    assert_eq!(string.capacity(), 3);
+
+    let mut string = String::new();
+    Output::for_string(&mut string).reserve(10);
+    assert!(string.capacity() >= 10);
+}
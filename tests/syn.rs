@@ -0,0 +1,131 @@
+#![cfg(feature = "syn")]
+
+use proc_macro2::{Ident, Literal, Span};
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::Token;
+
+use rust_jsx::{SnaxAttribute, SnaxItem, SnaxName, SnaxTag};
+
+/// Like quote!, but returns a single TokenTree instead
+macro_rules! quote_one {
+    ($($value: tt)*) => {
+        quote!($($value)*).into_iter().next().unwrap()
+    };
+}
+
+/// A macro input with more syntax before the markup and after it, like
+/// `html!(context, <div />, 5)`.
+struct Input {
+    before: Ident,
+    item: SnaxItem,
+    after: Literal,
+}
+
+impl Parse for Input {
+    fn parse(input: ParseStream) -> syn::Result<Input> {
+        let before = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let item = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let after = input.parse()?;
+
+        Ok(Input {
+            before,
+            item,
+            after,
+        })
+    }
+}
+
+#[test]
+fn item_in_larger_input() {
+    let input: Input = syn::parse2(quote!(context, <div><br /></div>, 5)).unwrap();
+
+    assert_eq!(input.before, "context");
+    assert_eq!(
+        input.item,
+        rust_jsx::parse(quote!(<div><br /></div>)).unwrap()
+    );
+    assert_eq!(input.after.to_string(), "5");
+}
+
+#[test]
+fn tag() {
+    let tag: SnaxTag = syn::parse2(quote!(<p>"hi"</p>)).unwrap();
+
+    assert_eq!(
+        tag,
+        SnaxTag {
+            name: SnaxName::new("p", Span::call_site()).into(),
+            attributes: Default::default(),
+            children: vec![SnaxItem::Content(quote_one!("hi"))],
+        }
+    );
+}
+
+#[test]
+fn tag_rejects_other_items() {
+    let error = syn::parse2::<SnaxTag>(quote!(<br />)).unwrap_err();
+
+    assert_eq!(error.to_string(), "expected a tag with a closing tag");
+}
+
+#[test]
+fn attributes() {
+    let attributes = syn::parse2::<Attributes>(quote!(
+        data-id="3" xlink:href={link} type disabled {title} {..props}
+    ))
+    .unwrap();
+
+    assert_eq!(
+        attributes.0,
+        vec![
+            SnaxAttribute::Simple {
+                name: SnaxName::new("data-id", Span::call_site()),
+                value: quote_one!("3"),
+            },
+            SnaxAttribute::Simple {
+                name: SnaxName::new("xlink:href", Span::call_site()),
+                value: quote_one!({ link }),
+            },
+            SnaxAttribute::Boolean {
+                name: SnaxName::new("type", Span::call_site()),
+            },
+            SnaxAttribute::Boolean {
+                name: SnaxName::new("disabled", Span::call_site()),
+            },
+            SnaxAttribute::Shorthand {
+                name: Ident::new("title", Span::call_site()),
+            },
+            SnaxAttribute::Spread(quote!(props)),
+        ]
+    );
+}
+
+#[test]
+fn attribute_with_bad_value() {
+    let error = syn::parse2::<SnaxAttribute>(quote!(id = x)).unwrap_err();
+
+    assert_eq!(error.to_string(), "unexpected token `x`");
+}
+
+#[test]
+fn errors_point_at_markup() {
+    let error = syn::parse2::<SnaxItem>(quote!(<div></span>)).unwrap_err();
+
+    assert_eq!(error.to_string(), "expected `</div>`, found `</span>`");
+}
+
+struct Attributes(Vec<SnaxAttribute>);
+
+impl Parse for Attributes {
+    fn parse(input: ParseStream) -> syn::Result<Attributes> {
+        let mut attributes = Vec::new();
+        while !input.is_empty() {
+            attributes.push(input.parse()?);
+        }
+
+        Ok(Attributes(attributes))
+    }
+}
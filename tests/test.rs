@@ -1,7 +1,10 @@
-use proc_macro2::{Ident, Span};
-use quote::quote;
+use proc_macro2::{Ident, Span, TokenStream, TokenTree};
+use quote::{quote, ToTokens};
 
-use rust_jsx::{SnaxAttribute, SnaxItem, SnaxSelfClosingTag, SnaxTag};
+use rust_jsx::{
+    ParseError, ParseStatus, Parser, SnaxAttribute, SnaxElse, SnaxItem, SnaxMatchArm, SnaxName,
+    SnaxPath, SnaxPathSegment, SnaxSelfClosingTag, SnaxTag, SnaxTagName,
+};
 
 /// Like quote!, but returns a single TokenTree instead
 macro_rules! quote_one {
@@ -34,7 +37,7 @@ fn empty_div() {
     let output = rust_jsx::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: SnaxName::new("div", Span::call_site()).into(),
         attributes: Default::default(),
         children: Default::default(),
     });
@@ -48,7 +51,7 @@ fn self_closing_div() {
     let output = rust_jsx::parse(input).unwrap();
 
     let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
-        name: Ident::new("div", Span::call_site()),
+        name: SnaxName::new("div", Span::call_site()).into(),
         attributes: Default::default(),
     });
 
@@ -61,7 +64,7 @@ fn empty_div_comment() {
     let output = rust_jsx::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: SnaxName::new("div", Span::call_site()).into(),
         attributes: Default::default(),
         children: Default::default(),
     });
@@ -75,14 +78,14 @@ fn empty_with_literal_attributes() {
     let output = rust_jsx::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: SnaxName::new("div", Span::call_site()).into(),
         attributes: vec![
             SnaxAttribute::Simple {
-                name: Ident::new("foo", Span::call_site()),
+                name: SnaxName::new("foo", Span::call_site()),
                 value: quote_one!("bar"),
             },
             SnaxAttribute::Simple {
-                name: Ident::new("baz", Span::call_site()),
+                name: SnaxName::new("baz", Span::call_site()),
                 value: quote_one!("qux"),
             },
         ],
@@ -98,9 +101,9 @@ fn empty_with_block_attribute() {
     let output = rust_jsx::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("label", Span::call_site()),
+        name: SnaxName::new("label", Span::call_site()).into(),
         attributes: vec![SnaxAttribute::Simple {
-            name: Ident::new("sum", Span::call_site()),
+            name: SnaxName::new("sum", Span::call_site()),
             value: quote_one!({ 5 + 5 }),
         }],
         children: Default::default(),
@@ -115,14 +118,14 @@ fn self_closing_with_literal_attributes() {
     let output = rust_jsx::parse(input).unwrap();
 
     let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
-        name: Ident::new("div", Span::call_site()),
+        name: SnaxName::new("div", Span::call_site()).into(),
         attributes: vec![
             SnaxAttribute::Simple {
-                name: Ident::new("foo", Span::call_site()),
+                name: SnaxName::new("foo", Span::call_site()),
                 value: quote_one!("bar"),
             },
             SnaxAttribute::Simple {
-                name: Ident::new("baz", Span::call_site()),
+                name: SnaxName::new("baz", Span::call_site()),
                 value: quote_one!("qux"),
             },
         ],
@@ -137,9 +140,9 @@ fn self_closing_with_block_attribute() {
     let output = rust_jsx::parse(input).unwrap();
 
     let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
-        name: Ident::new("label", Span::call_site()),
+        name: SnaxName::new("label", Span::call_site()).into(),
         attributes: vec![SnaxAttribute::Simple {
-            name: Ident::new("sum", Span::call_site()),
+            name: SnaxName::new("sum", Span::call_site()),
             value: quote_one!({ 5 + 5 }),
         }],
     });
@@ -157,14 +160,872 @@ fn nested_tags() {
     let output = rust_jsx::parse(input).unwrap();
 
     let expected = SnaxItem::Tag(SnaxTag {
-        name: Ident::new("div", Span::call_site()),
+        name: SnaxName::new("div", Span::call_site()).into(),
         attributes: Default::default(),
         children: vec![SnaxItem::Tag(SnaxTag {
-            name: Ident::new("span", Span::call_site()),
+            name: SnaxName::new("span", Span::call_site()).into(),
             attributes: Default::default(),
             children: Default::default(),
         })],
     });
 
     assert_eq!(output, expected);
-}
\ No newline at end of file
+}
+
+#[test]
+fn mismatched_close_tag() {
+    let input = quote!(<div></span>);
+
+    match rust_jsx::parse(input) {
+        Err(ParseError::MismatchedCloseTag { open, close }) => {
+            assert_eq!(open.to_string(), "div");
+            assert_eq!(close.to_string(), "span");
+        }
+        other => panic!("expected a mismatched close tag, got {:?}", other),
+    }
+}
+
+#[test]
+fn unclosed_tag() {
+    let input = quote!(<div><span></span>);
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "unexpected end of input, expected a closing tag"
+    );
+
+    match error {
+        ParseError::UnexpectedEnd {
+            unclosed: Some(_), ..
+        } => {}
+        other => panic!("expected an unexpected end, got {:?}", other),
+    }
+}
+
+#[test]
+fn unexpected_close_tag() {
+    let input = quote!(</div>);
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    assert_eq!(error.to_string(), "unexpected closing tag `</div>`");
+}
+
+#[test]
+fn error_to_compile_error() {
+    let input = quote!(<div></span>);
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    let expected = quote! {
+        compile_error!("expected `</div>`, found `</span>`");
+        compile_error!("`<div>` is opened here");
+    };
+
+    assert_eq!(error.to_compile_error().to_string(), expected.to_string());
+}
+
+#[test]
+fn fragment() {
+    let input = quote!(<>"Hello, " <b>"world"</b></>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Fragment(vec![
+        SnaxItem::Content(quote_one!("Hello, ")),
+        SnaxItem::Tag(SnaxTag {
+            name: SnaxName::new("b", Span::call_site()).into(),
+            attributes: Default::default(),
+            children: vec![SnaxItem::Content(quote_one!("world"))],
+        }),
+    ]);
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn nested_fragment() {
+    let input = quote!(<div><></></div>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxName::new("div", Span::call_site()).into(),
+        attributes: Default::default(),
+        children: vec![SnaxItem::Fragment(Default::default())],
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn fragment_closed_by_tag() {
+    let input = quote!(<></div>);
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    assert_eq!(error.to_string(), "unexpected closing tag `</div>`");
+}
+
+#[test]
+fn hyphenated_names() {
+    let input = quote!(<my-widget data-id="3" aria-label="x"></my-widget>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxName::new("my-widget", Span::call_site()).into(),
+        attributes: vec![
+            SnaxAttribute::Simple {
+                name: SnaxName::new("data-id", Span::call_site()),
+                value: quote_one!("3"),
+            },
+            SnaxAttribute::Simple {
+                name: SnaxName::new("aria-label", Span::call_site()),
+                value: quote_one!("x"),
+            },
+        ],
+        children: Default::default(),
+    });
+
+    assert_eq!(output, expected);
+
+    if let SnaxItem::Tag(tag) = output {
+        assert_eq!(tag.name.to_string(), "my-widget");
+    }
+}
+
+#[test]
+fn hyphenated_mismatched_close_tag() {
+    let input = quote!(<my-widget></my-gadget>);
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "expected `</my-widget>`, found `</my-gadget>`"
+    );
+}
+
+#[test]
+fn namespaced_names() {
+    let input = quote!(<svg:use xlink:href="#a" xml:lang="en" />);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: SnaxName::new("svg:use", Span::call_site()).into(),
+        attributes: vec![
+            SnaxAttribute::Simple {
+                name: SnaxName::new("xlink:href", Span::call_site()),
+                value: quote_one!("#a"),
+            },
+            SnaxAttribute::Simple {
+                name: SnaxName::new("xml:lang", Span::call_site()),
+                value: quote_one!("en"),
+            },
+        ],
+    });
+
+    assert_eq!(output, expected);
+
+    if let SnaxItem::SelfClosingTag(tag) = output {
+        let attribute = match &tag.attributes[0] {
+            SnaxAttribute::Simple { name, .. } => name,
+            other => panic!("expected a simple attribute, got {:?}", other),
+        };
+
+        assert_eq!(attribute.namespace.as_ref().unwrap()[0], "xlink");
+        assert_eq!(attribute.local[0], "href");
+        assert_eq!(attribute.to_string(), "xlink:href");
+    }
+}
+
+#[test]
+fn boolean_attributes() {
+    let input = quote!(<input disabled type="checkbox" checked />);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: SnaxName::new("input", Span::call_site()).into(),
+        attributes: vec![
+            SnaxAttribute::Boolean {
+                name: SnaxName::new("disabled", Span::call_site()),
+            },
+            SnaxAttribute::Simple {
+                name: SnaxName::new("type", Span::call_site()),
+                value: quote_one!("checkbox"),
+            },
+            SnaxAttribute::Boolean {
+                name: SnaxName::new("checked", Span::call_site()),
+            },
+        ],
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn boolean_attribute_on_open_tag() {
+    let input = quote!(<details open></details>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxName::new("details", Span::call_site()).into(),
+        attributes: vec![SnaxAttribute::Boolean {
+            name: SnaxName::new("open", Span::call_site()),
+        }],
+        children: Default::default(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn boolean_and_simple_attributes_differ() {
+    let boolean = SnaxAttribute::Boolean {
+        name: SnaxName::new("checked", Span::call_site()),
+    };
+    let simple = SnaxAttribute::Simple {
+        name: SnaxName::new("checked", Span::call_site()),
+        value: quote_one!("checked"),
+    };
+
+    assert_ne!(boolean, simple);
+}
+
+#[test]
+fn spread_attributes() {
+    let input = quote!(<div class="a" {..props} id="b" />);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: SnaxName::new("div", Span::call_site()).into(),
+        attributes: vec![
+            SnaxAttribute::Simple {
+                name: SnaxName::new("class", Span::call_site()),
+                value: quote_one!("a"),
+            },
+            SnaxAttribute::Spread(quote!(props)),
+            SnaxAttribute::Simple {
+                name: SnaxName::new("id", Span::call_site()),
+                value: quote_one!("b"),
+            },
+        ],
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn malformed_spread_attributes() {
+    for input in [quote!(<div {...props} />), quote!(<div {..=props} />)] {
+        match rust_jsx::parse(input) {
+            Err(ParseError::UnexpectedToken(TokenTree::Punct(ref punct)))
+                if punct.as_char() == '.' || punct.as_char() == '=' => {}
+            other => panic!("expected an unexpected token, got {:?}", other),
+        }
+    }
+}
+
+#[test]
+fn block_in_attribute_position() {
+    let input = quote!(<div { 1 + 1 } />);
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    match error {
+        ParseError::UnexpectedToken(TokenTree::Group(_)) => {}
+        other => panic!("expected an unexpected group, got {:?}", other),
+    }
+}
+
+#[test]
+fn shorthand_attributes() {
+    let input = quote!(<img {src} alt={alt} />);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: SnaxName::new("img", Span::call_site()).into(),
+        attributes: vec![
+            SnaxAttribute::Shorthand {
+                name: Ident::new("src", Span::call_site()),
+            },
+            SnaxAttribute::Simple {
+                name: SnaxName::new("alt", Span::call_site()),
+                value: quote_one!({ alt }),
+            },
+        ],
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn many_roots() {
+    let input = quote!(<li /> "text" <li></li>);
+    let output = rust_jsx::parse_many(input).unwrap();
+
+    let expected = vec![
+        SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+            name: SnaxName::new("li", Span::call_site()).into(),
+            attributes: Default::default(),
+        }),
+        SnaxItem::Content(quote_one!("text")),
+        SnaxItem::Tag(SnaxTag {
+            name: SnaxName::new("li", Span::call_site()).into(),
+            attributes: Default::default(),
+            children: Default::default(),
+        }),
+    ];
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn many_roots_empty() {
+    let output = rust_jsx::parse_many(quote!()).unwrap();
+
+    assert_eq!(output, Vec::new());
+}
+
+#[test]
+fn single_root_rejects_siblings() {
+    let input = quote!(<li /> <li />);
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    assert_eq!(error.to_string(), "unexpected token `<`");
+}
+
+#[test]
+fn if_else() {
+    let input = quote!(<div>{if logged_in { <a /> } else { "Log in" }}</div>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxName::new("div", Span::call_site()).into(),
+        attributes: Default::default(),
+        children: vec![SnaxItem::If {
+            condition: quote!(logged_in),
+            then: vec![SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+                name: SnaxName::new("a", Span::call_site()).into(),
+                attributes: Default::default(),
+            })],
+            else_: Some(SnaxElse::Block(vec![SnaxItem::Content(quote_one!(
+                "Log in"
+            ))])),
+        }],
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn else_if_chain() {
+    let input = quote!({
+        if count == 0 {
+            "none"
+        } else if count == 1 {
+            "one"
+        } else {
+            "many"
+        }
+    });
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::If {
+        condition: quote!(count == 0),
+        then: vec![SnaxItem::Content(quote_one!("none"))],
+        else_: Some(SnaxElse::If(Box::new(SnaxItem::If {
+            condition: quote!(count == 1),
+            then: vec![SnaxItem::Content(quote_one!("one"))],
+            else_: Some(SnaxElse::Block(vec![SnaxItem::Content(quote_one!("many"))])),
+        }))),
+    };
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn if_with_expression_branches_is_content() {
+    let input = quote!({
+        if a {
+            b
+        } else {
+            c
+        }
+    });
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Content(quote_one!({
+        if a {
+            b
+        } else {
+            c
+        }
+    }));
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn if_with_broken_markup() {
+    let input = quote!({if a { <div> }});
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    match error {
+        ParseError::UnexpectedEnd {
+            unclosed: Some(_), ..
+        } => {}
+        other => panic!("expected an unexpected end, got {:?}", other),
+    }
+}
+
+// Spans only have positions with `procmacro2_semver_exempt` enabled.
+#[cfg(procmacro2_semver_exempt)]
+#[test]
+fn unexpected_end_points_at_last_token() {
+    let input: TokenStream = "<div><span></span>\n\n\"x\"".parse().unwrap();
+
+    match rust_jsx::parse(input) {
+        Err(ParseError::UnexpectedEnd {
+            span,
+            unclosed: Some(unclosed),
+        }) => {
+            assert_eq!((span.start().line, span.start().column), (3, 0));
+            assert_eq!((unclosed.start().line, unclosed.start().column), (1, 1));
+        }
+        other => panic!("expected an unexpected end, got {:?}", other),
+    }
+}
+
+#[test]
+fn for_loop() {
+    let input =
+        quote!(<ul>{for (i, item) in items.iter().enumerate() { <li>{i} {item}</li> }}</ul>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxName::new("ul", Span::call_site()).into(),
+        attributes: Default::default(),
+        children: vec![SnaxItem::For {
+            pattern: quote!((i, item)),
+            iterable: quote!(items.iter().enumerate()),
+            body: vec![SnaxItem::Tag(SnaxTag {
+                name: SnaxName::new("li", Span::call_site()).into(),
+                attributes: Default::default(),
+                children: vec![
+                    SnaxItem::Content(quote_one!({ i })),
+                    SnaxItem::Content(quote_one!({ item })),
+                ],
+            })],
+        }],
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn for_loop_with_expression_body_is_content() {
+    let input = quote!({
+        for x in xs {
+            total += x;
+        }
+    });
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Content(quote_one!({
+        for x in xs {
+            total += x;
+        }
+    }));
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn match_arms() {
+    let input = quote!({match state {
+        State::Loading => <spinner />,
+        State::Failed(error) if error.is_fatal() => <p>"Oops"</p>,
+        State::Ready(data) => { "Ready: " {data} }
+    }});
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Match {
+        scrutinee: quote!(state),
+        arms: vec![
+            SnaxMatchArm {
+                pattern: quote!(State::Loading),
+                guard: None,
+                body: vec![SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+                    name: SnaxName::new("spinner", Span::call_site()).into(),
+                    attributes: Default::default(),
+                })],
+            },
+            SnaxMatchArm {
+                pattern: quote!(State::Failed(error)),
+                guard: Some(quote!(error.is_fatal())),
+                body: vec![SnaxItem::Tag(SnaxTag {
+                    name: SnaxName::new("p", Span::call_site()).into(),
+                    attributes: Default::default(),
+                    children: vec![SnaxItem::Content(quote_one!("Oops"))],
+                })],
+            },
+            SnaxMatchArm {
+                pattern: quote!(State::Ready(data)),
+                guard: None,
+                body: vec![
+                    SnaxItem::Content(quote_one!("Ready: ")),
+                    SnaxItem::Content(quote_one!({ data })),
+                ],
+            },
+        ],
+    };
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn match_with_expression_arms_is_content() {
+    let input = quote!({
+        match x {
+            Some(x) => x,
+            None => 0,
+        }
+    });
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Content(quote_one!({
+        match x {
+            Some(x) => x,
+            None => 0,
+        }
+    }));
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn let_binding() {
+    let input = quote!(<div>{let total: u32 = a + b;} {total}</div>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxName::new("div", Span::call_site()).into(),
+        attributes: Default::default(),
+        children: vec![
+            SnaxItem::Let {
+                pattern: quote!(total: u32),
+                init: quote!(a + b),
+            },
+            SnaxItem::Content(quote_one!({ total })),
+        ],
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn let_with_comparison() {
+    let input = quote!({let big = x >= 10});
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Let {
+        pattern: quote!(big),
+        init: quote!(x >= 10),
+    };
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn block_with_let_and_expression_is_content() {
+    let input = quote!({
+        let x = 5;
+        x * 2
+    });
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Content(quote_one!({
+        let x = 5;
+        x * 2
+    }));
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn doctype_and_comments() {
+    let input = quote!(
+        <!-- "Generated" -->
+        <!DOCTYPE html>
+        <html><!-- {note} --></html>
+    );
+    let output = rust_jsx::parse_many(input).unwrap();
+
+    let expected = vec![
+        SnaxItem::Comment(quote_one!("Generated")),
+        SnaxItem::Doctype {
+            name: Ident::new("html", Span::call_site()),
+        },
+        SnaxItem::Tag(SnaxTag {
+            name: SnaxName::new("html", Span::call_site()).into(),
+            attributes: Default::default(),
+            children: vec![SnaxItem::Comment(quote_one!({ note }))],
+        }),
+    ];
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn doctype_after_content() {
+    let input = quote!(<p /> <!DOCTYPE html>);
+    let error = rust_jsx::parse_many(input).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "`<!DOCTYPE>` is only allowed at the start of the document"
+    );
+}
+
+#[test]
+fn doctype_inside_tag() {
+    let input = quote!(<html><!DOCTYPE html></html>);
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "`<!DOCTYPE>` is only allowed at the start of the document"
+    );
+}
+
+#[test]
+fn doctype_inside_control_flow() {
+    let input = quote!({if full_page { <!DOCTYPE html> }});
+    let error = rust_jsx::parse(input).unwrap_err();
+
+    assert_eq!(
+        error.to_string(),
+        "`<!DOCTYPE>` is only allowed at the start of the document"
+    );
+}
+
+#[test]
+fn component_tags() {
+    let input = quote!(<ui::Button kind="primary">"Save"</ui::Button>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxTagName::Path(SnaxPath {
+            segments: vec![
+                SnaxPathSegment {
+                    ident: Ident::new("ui", Span::call_site()),
+                    arguments: None,
+                },
+                SnaxPathSegment {
+                    ident: Ident::new("Button", Span::call_site()),
+                    arguments: None,
+                },
+            ],
+        }),
+        attributes: vec![SnaxAttribute::Simple {
+            name: SnaxName::new("kind", Span::call_site()),
+            value: quote_one!("primary"),
+        }],
+        children: vec![SnaxItem::Content(quote_one!("Save"))],
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn capitalized_tags_are_components() {
+    let input = quote!(<Icon />);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::SelfClosingTag(SnaxSelfClosingTag {
+        name: SnaxPath::from(Ident::new("Icon", Span::call_site())).into(),
+        attributes: Default::default(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn generic_component_tags() {
+    let input = quote!(<List<Vec<Row>, fn() -> u8> compact></List<Vec<Row>, fn() -> u8>>);
+    let output = rust_jsx::parse(input).unwrap();
+
+    let expected = SnaxItem::Tag(SnaxTag {
+        name: SnaxTagName::Path(SnaxPath {
+            segments: vec![SnaxPathSegment {
+                ident: Ident::new("List", Span::call_site()),
+                arguments: Some(quote!(Vec<Row>, fn() -> u8)),
+            }],
+        }),
+        attributes: vec![SnaxAttribute::Boolean {
+            name: SnaxName::new("compact", Span::call_site()),
+        }],
+        children: Default::default(),
+    });
+
+    assert_eq!(output, expected);
+}
+
+#[test]
+fn mismatched_component_close_tag() {
+    let input = quote!(<ui::List<Row>></ui::List<Column>>);
+
+    match rust_jsx::parse(input) {
+        Err(ParseError::MismatchedCloseTag { open, close }) => {
+            assert_eq!(open.to_string(), "ui::List<Row>");
+            assert_eq!(close.to_string(), "ui::List<Column>");
+        }
+        other => panic!("expected a mismatched close tag, got {:?}", other),
+    }
+}
+
+#[test]
+fn many_attributes() {
+    // Tags are read in a single pass, so this stays fast even in debug builds.
+    let attributes: String = (0..4000).map(|i| format!(" a{}=\"x\"", i)).collect();
+    let input: TokenStream = format!("<div{}></div>", attributes).parse().unwrap();
+
+    match rust_jsx::parse(input) {
+        Ok(SnaxItem::Tag(tag)) => assert_eq!(tag.attributes.len(), 4000),
+        other => panic!("expected a tag, got {:?}", other),
+    }
+}
+
+#[test]
+fn streaming_parser() {
+    let mut parser = Parser::new();
+    let mut items = Vec::new();
+
+    for token in quote!(<ul><li>"one"</li></ul> <br />) {
+        match parser.feed(token).unwrap() {
+            ParseStatus::NeedMore => {}
+            ParseStatus::Item(item) => items.push(item),
+        }
+    }
+
+    assert!(parser.is_complete());
+    parser.finish().unwrap();
+    assert_eq!(
+        items,
+        rust_jsx::parse_many(quote!(<ul><li>"one"</li></ul> <br />)).unwrap()
+    );
+}
+
+#[test]
+fn streaming_parser_open_tags() {
+    let mut parser = Parser::new();
+
+    for token in quote!(<main><> <ui::List<Row>><li class=) {
+        assert_eq!(parser.feed(token).unwrap(), ParseStatus::NeedMore);
+    }
+
+    let open_tags: Vec<String> = parser.open_tags().map(ToString::to_string).collect();
+    assert_eq!(open_tags, ["main", "ui::List<Row>"]);
+    assert!(!parser.is_complete());
+
+    match parser.finish() {
+        Err(ParseError::UnexpectedEnd {
+            unclosed: Some(_), ..
+        }) => {}
+        other => panic!("expected an unexpected end, got {:?}", other),
+    }
+}
+
+#[test]
+fn streaming_parser_errors() {
+    let mut parser = Parser::new();
+
+    assert_eq!(parser.feed(quote_one!(<)).unwrap(), ParseStatus::NeedMore);
+    match parser.feed(quote_one!(5)) {
+        Err(ParseError::UnexpectedToken(_)) => {}
+        other => panic!("expected an unexpected token, got {:?}", other),
+    }
+}
+
+#[test]
+fn streaming_parser_many_attributes() {
+    let attributes: String = (0..4000).map(|i| format!(" a{}=\"x\"", i)).collect();
+    let input: TokenStream = format!("<div{}></div>", attributes).parse().unwrap();
+
+    let mut parser = Parser::new();
+    let mut items = Vec::new();
+    for token in input {
+        if let ParseStatus::Item(item) = parser.feed(token).unwrap() {
+            items.push(item);
+        }
+    }
+    parser.finish().unwrap();
+
+    match items.as_slice() {
+        [SnaxItem::Tag(tag)] => assert_eq!(tag.attributes.len(), 4000),
+        other => panic!("expected a tag, got {:?}", other),
+    }
+
+    let mut parser = Parser::new();
+    for token in quote!(<div 5 hidden) {
+        parser.feed(token).unwrap();
+    }
+    match parser.finish() {
+        Err(ParseError::UnexpectedToken(_)) => {}
+        other => panic!("expected an unexpected token, got {:?}", other),
+    }
+}
+
+/// Checks that every item in `input` prints back to tokens that parse into
+/// the same item.
+fn assert_round_trip(input: proc_macro2::TokenStream) {
+    for item in rust_jsx::parse_many(input).unwrap() {
+        let mut tokens = proc_macro2::TokenStream::new();
+        item.to_tokens(&mut tokens);
+
+        assert_eq!(rust_jsx::parse(tokens.clone()).unwrap(), item, "{}", tokens);
+    }
+}
+
+#[test]
+fn round_trip_tags() {
+    assert_round_trip(quote! {
+        <!DOCTYPE html>
+        <!-- "comment" -->
+        <div id="main" data-id={ 1 + 2 } xlink:href="#x" hidden {title} {..props}>
+            "text"
+            <br />
+            <>"in a fragment" <my-widget /></>
+            <!-- {note} -->
+        </div>
+        <ui::List<Vec<Row>, fn() -> u8> compact></ui::List<Vec<Row>, fn() -> u8>>
+        <Icon name="x" />
+    });
+}
+
+#[test]
+fn round_trip_control_flow() {
+    assert_round_trip(quote! {
+        <div>
+            {let total: i32 = items.iter().sum();}
+            {if total == 0 {
+                <em>"none"</em>
+            } else if total == 1 {
+                "one"
+            } else {
+                {total} " items"
+            }}
+            {for (i, item) in items.iter().enumerate() { <li>{i} {item}</li> }}
+            {match state {
+                State::Loading => <progress />,
+                State::Failed(error) if error.is_empty() => "Unknown error",
+                State::Ready(count) => { {count} " results" }
+            }}
+            {if flag { <a /> }}
+        </div>
+    });
+}
+
+#[test]
+fn round_trip_attributes() {
+    let attribute = SnaxAttribute::Simple {
+        name: SnaxName::new("aria-label", Span::call_site()),
+        value: quote_one!("Close"),
+    };
+
+    assert_eq!(
+        attribute.into_token_stream().to_string(),
+        quote!(aria - label = "Close").to_string()
+    );
+}
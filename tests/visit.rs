@@ -0,0 +1,178 @@
+use proc_macro2::{Ident, Literal, Span, TokenStream, TokenTree};
+use quote::quote;
+
+use rust_jsx::visit::{self, Visit};
+use rust_jsx::visit_mut::{self, VisitMut};
+use rust_jsx::{SnaxAttribute, SnaxName, SnaxTagName};
+
+/// Collects the names of every tag and attribute, and every content token.
+#[derive(Default)]
+struct Collect {
+    tags: Vec<String>,
+    attributes: Vec<String>,
+    content: Vec<String>,
+}
+
+impl<'ast> Visit<'ast> for Collect {
+    fn visit_tag_name(&mut self, name: &'ast SnaxTagName) {
+        self.tags.push(name.to_string());
+    }
+
+    fn visit_attribute(&mut self, attribute: &'ast SnaxAttribute) {
+        if let SnaxAttribute::Simple { name, .. } | SnaxAttribute::Boolean { name } = attribute {
+            self.attributes.push(name.to_string());
+        }
+    }
+
+    fn visit_content(&mut self, content: &'ast TokenTree) {
+        self.content.push(content.to_string());
+    }
+}
+
+#[test]
+fn visit_walks_every_node() {
+    let item = rust_jsx::parse(quote! {
+        <ul class="list">
+            {for item in items {
+                <li hidden>{item}</li>
+            }}
+            {if show { <ui::Footer /> } else { <>"none"</> }}
+            {match state {
+                State::Ready => <p data-id="3" />,
+                _ => "other"
+            }}
+        </ul>
+    })
+    .unwrap();
+
+    let mut collect = Collect::default();
+    collect.visit_item(&item);
+
+    assert_eq!(collect.tags, ["ul", "li", "ui::Footer", "p"]);
+    assert_eq!(collect.attributes, ["class", "hidden", "data-id"]);
+    assert_eq!(collect.content, ["{item}", "\"none\"", "\"other\""]);
+}
+
+/// Stops at `svg` tags, without visiting what's inside them.
+#[derive(Default)]
+struct SkipSvg(Vec<String>);
+
+impl<'ast> Visit<'ast> for SkipSvg {
+    fn visit_tag(&mut self, tag: &'ast rust_jsx::SnaxTag) {
+        self.0.push(tag.name.to_string());
+
+        if tag.name.to_string() != "svg" {
+            visit::visit_tag(self, tag);
+        }
+    }
+}
+
+#[test]
+fn visit_can_skip_children() {
+    let item = rust_jsx::parse(quote!(<div><svg><g></g></svg><p></p></div>)).unwrap();
+
+    let mut skip = SkipSvg::default();
+    skip.visit_item(&item);
+
+    assert_eq!(skip.0, ["div", "svg", "p"]);
+}
+
+/// Renames `class` attributes to `className`.
+struct RenameClass;
+
+impl VisitMut for RenameClass {
+    fn visit_name_mut(&mut self, name: &mut SnaxName) {
+        if name == "class" {
+            *name = SnaxName::from(Ident::new("className", Span::call_site()));
+        }
+
+        visit_mut::visit_name_mut(self, name);
+    }
+}
+
+#[test]
+fn visit_mut_changes_nodes_in_place() {
+    let mut item =
+        rust_jsx::parse(quote!(<div class="a">{if x { <span class="b" /> }}</div>)).unwrap();
+    RenameClass.visit_item_mut(&mut item);
+
+    assert_eq!(
+        item,
+        rust_jsx::parse(quote!(<div className="a">{if x { <span className="b" /> }}</div>))
+            .unwrap()
+    );
+}
+
+/// Collects the leaves that don't have a node type of their own.
+#[derive(Default)]
+struct Leaves(Vec<String>);
+
+impl<'ast> Visit<'ast> for Leaves {
+    fn visit_attribute_value(&mut self, value: &'ast TokenTree) {
+        self.0.push(format!("value {}", value));
+    }
+
+    fn visit_spread(&mut self, props: &'ast TokenStream) {
+        self.0.push(format!("spread {}", props));
+    }
+
+    fn visit_shorthand(&mut self, name: &'ast Ident) {
+        self.0.push(format!("shorthand {}", name));
+    }
+
+    fn visit_comment(&mut self, content: &'ast TokenTree) {
+        self.0.push(format!("comment {}", content));
+    }
+
+    fn visit_let(&mut self, pattern: &'ast TokenStream, init: &'ast TokenStream) {
+        self.0.push(format!("let {} = {}", pattern, init));
+    }
+
+    fn visit_doctype(&mut self, name: &'ast Ident) {
+        self.0.push(format!("doctype {}", name));
+    }
+}
+
+#[test]
+fn visit_reaches_every_leaf() {
+    let items = rust_jsx::parse_many(quote! {
+        <!DOCTYPE html>
+        <!-- "note" -->
+        <a href="/home" {title} {..props}>{let x = 1;}</a>
+    })
+    .unwrap();
+
+    let mut leaves = Leaves::default();
+    for item in &items {
+        leaves.visit_item(item);
+    }
+
+    assert_eq!(
+        leaves.0,
+        [
+            "doctype html",
+            "comment \"note\"",
+            "value \"/home\"",
+            "shorthand title",
+            "spread props",
+            "let x = 1",
+        ]
+    );
+}
+
+/// Points every `href` at `#`.
+struct ClearLinks;
+
+impl VisitMut for ClearLinks {
+    fn visit_attribute_value_mut(&mut self, value: &mut TokenTree) {
+        *value = TokenTree::Literal(Literal::string("#"));
+    }
+}
+
+#[test]
+fn visit_mut_changes_attribute_values() {
+    let mut item = rust_jsx::parse(quote!(<a href={url}>"x"</a>)).unwrap();
+    ClearLinks.visit_item_mut(&mut item);
+
+    assert_eq!(item, rust_jsx::parse(quote!(<a href="#">"x"</a>)).unwrap());
+}